@@ -165,7 +165,7 @@ impl StateCheck {
         if let Some(ref balances) = self.balances {
             for (index, comparison, expected) in balances {
                 let actual = *state
-                    .validator_balances
+                    .validator_balances()
                     .get(*index as usize)
                     .expect("Balance check specifies unknown validator");
 
@@ -64,7 +64,7 @@ where
 
         for index in active_validator_indices {
             let balance = std::cmp::min(
-                current_state.validator_balances[index],
+                current_state.validator_balances()[index],
                 spec.max_deposit_amount,
             ) / spec.fork_choice_balance_increment;
             if balance > 0 {
@@ -0,0 +1,85 @@
+use super::verify_bitfield_length;
+use types::*;
+
+/// Returns the position of `pubkey` in `committee`, if it is present.
+///
+/// The position is the index of the validator's bit in an attestation bitfield for `committee`.
+/// Comparing public keys is cheap, it compares their cached compressed bytes.
+pub fn committee_contains(pubkey: &PublicKey, committee: &[PublicKey]) -> Option<usize> {
+    committee.iter().position(|member| member == pubkey)
+}
+
+/// Returns `true` if the bit at `index` is set in `bitfield`.
+///
+/// Returns `false` if `index` is out of bounds.
+pub fn bitfield_marks(index: usize, bitfield: &Bitfield) -> bool {
+    bitfield.get(index).unwrap_or(false)
+}
+
+/// Returns the aggregate of the members of `committee` whose bit is set in `bitfield`.
+///
+/// Returns `BeaconStateError::InvalidBitfield` if `bitfield` is not the length required for
+/// `committee` (see `verify_bitfield_length`).
+pub fn aggregate_selected(
+    committee: &[PublicKey],
+    bitfield: &Bitfield,
+) -> Result<AggregatePublicKey, BeaconStateError> {
+    if !verify_bitfield_length(bitfield, committee.len()) {
+        return Err(BeaconStateError::InvalidBitfield);
+    }
+
+    let mut aggregate = AggregatePublicKey::new();
+    for (i, member) in committee.iter().enumerate() {
+        if bitfield_marks(i, bitfield) {
+            aggregate.add(member);
+        }
+    }
+
+    Ok(aggregate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locate_committee_member() {
+        let keypairs: Vec<Keypair> = (0..4).map(|_| Keypair::random()).collect();
+        let committee: Vec<PublicKey> = keypairs.iter().map(|kp| kp.pk.clone()).collect();
+
+        assert_eq!(committee_contains(&keypairs[2].pk, &committee), Some(2));
+        assert_eq!(committee_contains(&Keypair::random().pk, &committee), None);
+
+        let mut bitfield = Bitfield::from_elem(committee.len(), false);
+        bitfield.set(2, true);
+
+        let index = committee_contains(&keypairs[2].pk, &committee).unwrap();
+        assert!(bitfield_marks(index, &bitfield));
+        assert!(!bitfield_marks(1, &bitfield));
+        assert!(!bitfield_marks(64, &bitfield));
+    }
+
+    #[test]
+    fn aggregate_selected_members() {
+        let committee: Vec<PublicKey> = (0..10).map(|_| Keypair::random().pk).collect();
+
+        let mut bitfield = Bitfield::from_elem(committee.len(), false);
+        bitfield.set(1, true);
+        bitfield.set(4, true);
+        bitfield.set(9, true);
+
+        let expected =
+            AggregatePublicKey::from_public_keys(&[&committee[1], &committee[4], &committee[9]]);
+        assert_eq!(aggregate_selected(&committee, &bitfield), Ok(expected));
+
+        // The bitfield must have a bit for each member, and no more bytes.
+        assert_eq!(
+            aggregate_selected(&committee[..4], &bitfield),
+            Err(BeaconStateError::InvalidBitfield)
+        );
+        assert_eq!(
+            aggregate_selected(&committee, &Bitfield::new()),
+            Err(BeaconStateError::InvalidBitfield)
+        );
+    }
+}
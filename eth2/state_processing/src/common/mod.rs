@@ -1,7 +1,9 @@
+mod committee_membership;
 mod exit_validator;
 mod slash_validator;
 mod verify_bitfield;
 
+pub use committee_membership::{aggregate_selected, bitfield_marks, committee_contains};
 pub use exit_validator::exit_validator;
 pub use slash_validator::slash_validator;
 pub use verify_bitfield::verify_bitfield_length;
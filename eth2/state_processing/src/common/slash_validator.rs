@@ -12,7 +12,7 @@ pub fn slash_validator(
     let current_epoch = state.current_epoch(spec);
 
     if (validator_index >= state.validator_registry.len())
-        | (validator_index >= state.validator_balances.len())
+        | (validator_index >= state.validator_balances().len())
     {
         return Err(BeaconStateError::UnknownValidator);
     }
@@ -45,11 +45,11 @@ pub fn slash_validator(
     let whistleblower_reward = effective_balance / spec.whistleblower_reward_quotient;
 
     safe_add_assign!(
-        state.validator_balances[whistleblower_index as usize],
+        state.validator_balances_mut()[whistleblower_index as usize],
         whistleblower_reward
     );
     safe_sub_assign!(
-        state.validator_balances[validator_index],
+        state.validator_balances_mut()[validator_index],
         whistleblower_reward
     );
 
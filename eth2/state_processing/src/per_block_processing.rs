@@ -381,7 +381,7 @@ pub fn process_deposits(
         if let Some(index) = validator_index {
             // Update the existing validator balance.
             safe_add_assign!(
-                state.validator_balances[index as usize],
+                state.validator_balances_mut()[index as usize],
                 deposit_data.amount
             );
         } else {
@@ -396,7 +396,7 @@ pub fn process_deposits(
                 slashed: false,
             };
             state.validator_registry.push(validator);
-            state.validator_balances.push(deposit_data.amount);
+            state.validator_balances_mut().push(deposit_data.amount);
         }
 
         state.deposit_index += 1;
@@ -36,7 +36,7 @@ fn verify_transfer_parametric(
     time_independent_only: bool,
 ) -> Result<(), Error> {
     let sender_balance = *state
-        .validator_balances
+        .validator_balances()
         .get(transfer.sender as usize)
         .ok_or_else(|| Error::Invalid(Invalid::FromValidatorUnknown(transfer.sender)))?;
 
@@ -129,24 +129,24 @@ pub fn execute_transfer(
     spec: &ChainSpec,
 ) -> Result<(), Error> {
     let sender_balance = *state
-        .validator_balances
+        .validator_balances()
         .get(transfer.sender as usize)
         .ok_or_else(|| Error::Invalid(Invalid::FromValidatorUnknown(transfer.sender)))?;
     let recipient_balance = *state
-        .validator_balances
+        .validator_balances()
         .get(transfer.recipient as usize)
         .ok_or_else(|| Error::Invalid(Invalid::ToValidatorUnknown(transfer.recipient)))?;
 
     let proposer_index =
         state.get_beacon_proposer_index(state.slot, RelativeEpoch::Current, spec)?;
-    let proposer_balance = state.validator_balances[proposer_index];
+    let proposer_balance = state.validator_balances()[proposer_index];
 
     let total_amount = transfer
         .amount
         .checked_add(transfer.fee)
         .ok_or_else(|| Error::Invalid(Invalid::FeeOverflow(transfer.amount, transfer.fee)))?;
 
-    state.validator_balances[transfer.sender as usize] =
+    state.validator_balances_mut()[transfer.sender as usize] =
         sender_balance.checked_sub(total_amount).ok_or_else(|| {
             Error::Invalid(Invalid::FromBalanceInsufficient(
                 total_amount,
@@ -154,7 +154,7 @@ pub fn execute_transfer(
             ))
         })?;
 
-    state.validator_balances[transfer.recipient as usize] = recipient_balance
+    state.validator_balances_mut()[transfer.recipient as usize] = recipient_balance
         .checked_add(transfer.amount)
         .ok_or_else(|| {
             Error::Invalid(Invalid::ToBalanceOverflow(
@@ -163,7 +163,7 @@ pub fn execute_transfer(
             ))
         })?;
 
-    state.validator_balances[proposer_index] =
+    state.validator_balances_mut()[proposer_index] =
         proposer_balance.checked_add(transfer.fee).ok_or_else(|| {
             Error::Invalid(Invalid::ProposerBalanceOverflow(
                 proposer_balance,
@@ -1,4 +1,5 @@
 use apply_rewards::apply_rewards;
+use attestation_data_cache::AttestationDataCache;
 use errors::EpochProcessingError as Error;
 use process_ejections::process_ejections;
 use process_exit_queue::process_exit_queue;
@@ -8,15 +9,17 @@ use std::collections::HashMap;
 use types::*;
 use update_registry_and_shuffling_data::update_registry_and_shuffling_data;
 use validator_statuses::{TotalBalances, ValidatorStatuses};
-use winning_root::{winning_root, WinningRoot};
+use winning_root::{winning_root_with_cache, WinningRoot};
 
 pub mod apply_rewards;
+pub mod attestation_data_cache;
 pub mod errors;
 pub mod get_attestation_participants;
 pub mod inclusion_distance;
 pub mod process_ejections;
 pub mod process_exit_queue;
 pub mod process_slashings;
+pub mod shard_committee_bitmap;
 pub mod tests;
 pub mod update_registry_and_shuffling_data;
 pub mod validator_statuses;
@@ -38,28 +41,15 @@ pub fn per_epoch_processing(state: &mut BeaconState, spec: &ChainSpec) -> Result
     state.build_epoch_cache(RelativeEpoch::Previous, spec)?;
     state.build_epoch_cache(RelativeEpoch::Current, spec)?;
 
-    // Load the struct we use to assign validators into sets based on their participation.
-    //
-    // E.g., attestation in the previous epoch, attested to the head, etc.
-    let mut validator_statuses = ValidatorStatuses::new(state, spec)?;
-    validator_statuses.process_attestations(&state, spec)?;
-
-    // Justification.
-    update_justification_and_finalization(state, &validator_statuses.total_balances, spec)?;
+    // Balances are not modified until rewards are applied, until then effective balances (e.g.,
+    // for winning roots) are read from the cache.
+    state.build_effective_balance_cache(spec);
 
-    // Crosslinks.
-    let winning_root_for_shards = process_crosslinks(state, spec)?;
-
-    // Eth1 data.
-    maybe_reset_eth1_period(state, spec);
-
-    // Rewards and Penalities.
-    apply_rewards(
-        state,
-        &mut validator_statuses,
-        &winning_root_for_shards,
-        spec,
-    )?;
+    // Rewards modify balances, the cache is dropped afterwards (or on any error before then) so it
+    // is never left stale on the state.
+    let result = process_rewards_and_penalties(state, spec);
+    state.drop_effective_balance_cache();
+    let validator_statuses = result?;
 
     // Ejections.
     process_ejections(state, spec)?;
@@ -84,6 +74,38 @@ pub fn per_epoch_processing(state: &mut BeaconState, spec: &ChainSpec) -> Result
     Ok(())
 }
 
+/// Performs the steps of per-epoch processing up to and including the application of rewards and
+/// penalties, returning the `ValidatorStatuses` used for the remaining steps.
+fn process_rewards_and_penalties(
+    state: &mut BeaconState,
+    spec: &ChainSpec,
+) -> Result<ValidatorStatuses, Error> {
+    // Load the struct we use to assign validators into sets based on their participation.
+    //
+    // E.g., attestation in the previous epoch, attested to the head, etc.
+    let mut validator_statuses = ValidatorStatuses::new(state, spec)?;
+    validator_statuses.process_attestations(&state, spec)?;
+
+    // Justification.
+    update_justification_and_finalization(state, &validator_statuses.total_balances, spec)?;
+
+    // Crosslinks.
+    let winning_root_for_shards = process_crosslinks(state, spec)?;
+
+    // Eth1 data.
+    maybe_reset_eth1_period(state, spec);
+
+    // Rewards and Penalities.
+    apply_rewards(
+        state,
+        &mut validator_statuses,
+        &winning_root_for_shards,
+        spec,
+    )?;
+
+    Ok(validator_statuses)
+}
+
 /// Maybe resets the eth1 period.
 ///
 /// Spec v0.5.0
@@ -184,6 +206,7 @@ pub fn process_crosslinks(
     spec: &ChainSpec,
 ) -> Result<WinningRootHashSet, Error> {
     let mut winning_root_for_shards: WinningRootHashSet = HashMap::new();
+    let mut attestation_data_cache = AttestationDataCache::new();
 
     let previous_and_current_epoch_slots: Vec<Slot> = state
         .previous_epoch(spec)
@@ -199,7 +222,8 @@ pub fn process_crosslinks(
         for c in crosslink_committees_at_slot {
             let shard = c.shard as u64;
 
-            let winning_root = winning_root(state, shard, spec)?;
+            let winning_root =
+                winning_root_with_cache(state, shard, &mut attestation_data_cache, spec)?;
 
             if let Some(winning_root) = winning_root {
                 let total_committee_balance = state.get_total_balance(&c.committee, spec)?;
@@ -219,6 +243,25 @@ pub fn process_crosslinks(
     Ok(winning_root_for_shards)
 }
 
+/// Returns the `(shard, crosslink_data_root)` of each of the `winners` with at least two-thirds of
+/// the balance of its committee, sorted by shard.
+///
+/// Uses the `committee_total_balance` of each `WinningRoot`, the committees are not re-scanned.
+/// The threshold is that of `process_crosslinks`.
+pub fn finalizable_crosslinks(winners: &WinningRootHashSet) -> Vec<(u64, Hash256)> {
+    let mut crosslinks: Vec<(u64, Hash256)> = winners
+        .iter()
+        .filter(|(_, winning_root)| {
+            (3 * winning_root.total_attesting_balance) >= (2 * winning_root.committee_total_balance)
+        })
+        .map(|(shard, winning_root)| (*shard, winning_root.crosslink_data_root))
+        .collect();
+
+    crosslinks.sort_unstable();
+
+    crosslinks
+}
+
 /// Finish up an epoch update.
 ///
 /// Spec v0.5.0
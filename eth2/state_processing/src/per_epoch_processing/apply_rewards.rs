@@ -40,7 +40,7 @@ pub fn apply_rewards(
     spec: &ChainSpec,
 ) -> Result<(), Error> {
     // Guard against an out-of-bounds during the validator balance update.
-    if validator_statuses.statuses.len() != state.validator_balances.len() {
+    if validator_statuses.statuses.len() != state.validator_balances().len() {
         return Err(Error::ValidatorStatusesInconsistent);
     }
     // Guard against an out-of-bounds during the attester inclusion balance update.
@@ -48,7 +48,7 @@ pub fn apply_rewards(
         return Err(Error::ValidatorStatusesInconsistent);
     }
 
-    let mut deltas = vec![Delta::default(); state.validator_balances.len()];
+    let mut deltas = vec![Delta::default(); state.validator_balances().len()];
 
     get_justification_and_finalization_deltas(&mut deltas, state, &validator_statuses, spec)?;
     get_crosslink_deltas(&mut deltas, state, &validator_statuses, spec)?;
@@ -68,9 +68,10 @@ pub fn apply_rewards(
     }
 
     // Apply the deltas, over-flowing but not under-flowing (saturating at 0 instead).
+    let balances = state.validator_balances_mut();
     for (i, delta) in deltas.iter().enumerate() {
-        state.validator_balances[i] += delta.rewards;
-        state.validator_balances[i] = state.validator_balances[i].saturating_sub(delta.penalties);
+        balances[i] += delta.rewards;
+        balances[i] = balances[i].saturating_sub(delta.penalties);
     }
 
     Ok(())
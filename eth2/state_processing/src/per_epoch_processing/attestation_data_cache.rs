@@ -0,0 +1,118 @@
+use super::get_attestation_participants::get_attestation_participants;
+use ssz::TreeHash;
+use std::collections::HashMap;
+use types::*;
+
+/// Caches the participants of each distinct `AttestationData` in a set of pending attestations.
+///
+/// Entries are keyed by the `hash_tree_root` of the `AttestationData` and are populated lazily.
+/// The participants of all pending attestations sharing the same data are merged into a single
+/// sorted, de-duplicated list.
+///
+/// A cache should only be used with the state and attestations it was first populated from,
+/// however it remains valid whilst `latest_crosslinks` is updated during crosslink processing.
+#[derive(Debug, Default, Clone)]
+pub struct AttestationDataCache {
+    participants: HashMap<Hash256, Vec<usize>>,
+}
+
+impl AttestationDataCache {
+    /// Returns an empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of distinct `AttestationData` in the cache.
+    pub fn len(&self) -> usize {
+        self.participants.len()
+    }
+
+    /// Returns `true` if the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.participants.is_empty()
+    }
+
+    /// Returns all validator indices which attested to `data` in any of the `attestations`.
+    ///
+    /// The indices are computed on the first request for `data`, subsequent requests read from
+    /// the cache (and ignore `attestations`).
+    pub fn get_participants<'a>(
+        &mut self,
+        state: &BeaconState,
+        data: &AttestationData,
+        attestations: impl Iterator<Item = &'a PendingAttestation>,
+        spec: &ChainSpec,
+    ) -> Result<&[usize], BeaconStateError> {
+        let root = data.tree_hash_root();
+
+        if !self.participants.contains_key(&root) {
+            let mut indices = vec![];
+
+            for a in attestations.filter(|a| a.data == *data) {
+                indices.append(&mut get_attestation_participants(
+                    state,
+                    &a.data,
+                    &a.aggregation_bitfield,
+                    spec,
+                )?);
+            }
+
+            indices.sort_unstable();
+            indices.dedup();
+
+            self.participants.insert(root, indices);
+        }
+
+        Ok(&self.participants[&root])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::test_utils::TestingBeaconStateBuilder;
+
+    #[test]
+    fn cache_hits_match_fresh_lookups() {
+        let spec = ChainSpec::few_validators();
+
+        let mut builder = TestingBeaconStateBuilder::from_deterministic_keypairs(8, &spec);
+        let slot = (spec.genesis_epoch + 4).end_slot(spec.slots_per_epoch);
+        builder.teleport_to_slot(slot, &spec);
+        builder.insert_attestations(&spec);
+        let (state, _keypairs) = builder.build();
+
+        let mut cache = AttestationDataCache::new();
+
+        let attestations = || {
+            state
+                .previous_epoch_attestations
+                .iter()
+                .chain(state.current_epoch_attestations.iter())
+        };
+
+        for a in attestations() {
+            let mut fresh =
+                get_attestation_participants(&state, &a.data, &a.aggregation_bitfield, &spec)
+                    .unwrap();
+            fresh.sort_unstable();
+
+            let first = cache
+                .get_participants(&state, &a.data, attestations(), &spec)
+                .unwrap()
+                .to_vec();
+            let len = cache.len();
+            let second = cache
+                .get_participants(&state, &a.data, attestations(), &spec)
+                .unwrap()
+                .to_vec();
+
+            assert_eq!(first, fresh);
+            assert_eq!(first, second);
+            // The second lookup must not have added an entry.
+            assert_eq!(cache.len(), len);
+        }
+
+        assert!(!cache.is_empty());
+    }
+}
@@ -3,6 +3,9 @@ use types::*;
 
 /// Returns validator indices which participated in the attestation.
 ///
+/// Returns `BeaconStateError::NoCommitteeForShard` if the state does not assign a committee to
+/// the attestation's shard at the attestation's slot.
+///
 /// Spec v0.5.0
 pub fn get_attestation_participants(
     state: &BeaconState,
@@ -12,11 +15,20 @@ pub fn get_attestation_participants(
 ) -> Result<Vec<usize>, BeaconStateError> {
     let epoch = attestation_data.slot.epoch(spec.slots_per_epoch);
 
-    let crosslink_committee =
-        state.get_crosslink_committee_for_shard(epoch, attestation_data.shard, spec)?;
+    let no_committee = || BeaconStateError::NoCommitteeForShard {
+        shard: attestation_data.shard,
+        slot: attestation_data.slot,
+    };
+
+    let crosslink_committee = state
+        .get_crosslink_committee_for_shard(epoch, attestation_data.shard, spec)
+        .map_err(|e| match e {
+            BeaconStateError::NoCommitteeForShardInEpoch { .. } => no_committee(),
+            e => e,
+        })?;
 
     if crosslink_committee.slot != attestation_data.slot {
-        return Err(BeaconStateError::NoCommitteeForShard);
+        return Err(no_committee());
     }
 
     let committee = &crosslink_committee.committee;
@@ -36,3 +48,64 @@ pub fn get_attestation_participants(
 
     Ok(participants)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::test_utils::TestingBeaconStateBuilder;
+
+    fn build_state(spec: &ChainSpec) -> BeaconState {
+        let mut builder = TestingBeaconStateBuilder::from_deterministic_keypairs(8, spec);
+        let slot = (spec.genesis_epoch + 2).start_slot(spec.slots_per_epoch);
+        builder.teleport_to_slot(slot, spec);
+        builder.build_caches(spec).unwrap();
+        let (state, _keypairs) = builder.build();
+        state
+    }
+
+    #[test]
+    fn no_committee_for_shard() {
+        let spec = ChainSpec::few_validators();
+        let state = build_state(&spec);
+
+        let data = AttestationData {
+            slot: state.slot,
+            shard: spec.shard_count,
+            ..AttestationData::default()
+        };
+
+        assert_eq!(
+            get_attestation_participants(&state, &data, &Bitfield::new(), &spec),
+            Err(BeaconStateError::NoCommitteeForShard {
+                shard: spec.shard_count,
+                slot: state.slot,
+            })
+        );
+    }
+
+    #[test]
+    fn no_committee_for_shard_at_slot() {
+        let spec = ChainSpec::few_validators();
+        let state = build_state(&spec);
+
+        let committee = state
+            .get_crosslink_committees_at_slot(state.slot, &spec)
+            .unwrap()[0]
+            .clone();
+
+        // The committee exists for the shard, but at a different slot.
+        let data = AttestationData {
+            slot: state.slot + 1,
+            shard: committee.shard,
+            ..AttestationData::default()
+        };
+
+        assert_eq!(
+            get_attestation_participants(&state, &data, &Bitfield::new(), &spec),
+            Err(BeaconStateError::NoCommitteeForShard {
+                shard: committee.shard,
+                slot: state.slot + 1,
+            })
+        );
+    }
+}
@@ -12,7 +12,7 @@ pub fn process_ejections(state: &mut BeaconState, spec: &ChainSpec) -> Result<()
         .get_cached_active_validator_indices(RelativeEpoch::Current, spec)?
         .iter()
         .filter_map(|&i| {
-            if state.validator_balances[i as usize] < spec.ejection_balance {
+            if state.validator_balances()[i as usize] < spec.ejection_balance {
                 Some(i)
             } else {
                 None
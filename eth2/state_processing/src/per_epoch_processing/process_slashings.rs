@@ -27,7 +27,7 @@ pub fn process_slashings(
                 effective_balance / spec.min_penalty_quotient,
             );
 
-            state.validator_balances[index] -= penalty;
+            state.validator_balances_mut()[index] -= penalty;
         }
     }
 
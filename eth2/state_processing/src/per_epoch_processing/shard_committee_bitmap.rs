@@ -0,0 +1,135 @@
+use crate::common::{bitfield_marks, verify_bitfield_length};
+use std::collections::HashMap;
+use types::*;
+
+/// Precomputes the crosslink committees of the previous and current epochs of some
+/// `BeaconState`, along with a bitmap of the validators which may attest to each shard.
+///
+/// Attestation participants are found by intersecting an attestation's bitfield with the stored
+/// committee, avoiding a committee lookup on the state for each attestation.
+///
+/// A bitmap should only be used with the state it was built from (or a state with identical
+/// committees for the previous and current epochs).
+#[derive(Debug, Default, Clone)]
+pub struct ShardCommitteeBitmap {
+    /// The committee members for each `(slot, shard)`, in the order of attestation bitfields.
+    committees: HashMap<(Slot, Shard), Vec<usize>>,
+    /// For each shard, a bitmap (indexed by validator index) of its committee members.
+    members: HashMap<Shard, Bitfield>,
+}
+
+impl ShardCommitteeBitmap {
+    /// Builds the bitmap from the committees of the previous and current epochs of `state`.
+    pub fn new(state: &BeaconState, spec: &ChainSpec) -> Result<Self, BeaconStateError> {
+        let mut bitmap = Self::default();
+        let validator_count = state.validator_registry.len();
+
+        for epoch in &[state.previous_epoch(spec), state.current_epoch(spec)] {
+            for slot in epoch.slot_iter(spec.slots_per_epoch) {
+                for c in state.get_crosslink_committees_at_slot(slot, spec)? {
+                    let members = bitmap
+                        .members
+                        .entry(c.shard)
+                        .or_insert_with(|| Bitfield::from_elem(validator_count, false));
+                    for &i in &c.committee {
+                        members.set(i, true);
+                    }
+
+                    bitmap
+                        .committees
+                        .insert((c.slot, c.shard), c.committee.clone());
+                }
+            }
+        }
+
+        Ok(bitmap)
+    }
+
+    /// Returns `true` if `validator_index` is a member of any committee for `shard`.
+    pub fn can_attest(&self, shard: Shard, validator_index: usize) -> bool {
+        self.members
+            .get(&shard)
+            .map_or(false, |members| bitfield_marks(validator_index, members))
+    }
+
+    /// Returns validator indices which participated in the attestation.
+    ///
+    /// Equivalent to `get_attestation_participants`, including the errors returned.
+    pub fn get_participants(
+        &self,
+        attestation_data: &AttestationData,
+        bitfield: &Bitfield,
+    ) -> Result<Vec<usize>, BeaconStateError> {
+        let committee = self
+            .committees
+            .get(&(attestation_data.slot, attestation_data.shard))
+            .ok_or_else(|| BeaconStateError::NoCommitteeForShard {
+                shard: attestation_data.shard,
+                slot: attestation_data.slot,
+            })?;
+
+        if !verify_bitfield_length(bitfield, committee.len()) {
+            return Err(BeaconStateError::InvalidBitfield);
+        }
+
+        Ok(committee
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| bitfield_marks(*i, bitfield))
+            .map(|(_, validator_index)| *validator_index)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::get_attestation_participants::get_attestation_participants;
+    use super::*;
+    use types::test_utils::TestingBeaconStateBuilder;
+
+    #[test]
+    fn matches_get_attestation_participants() {
+        let spec = ChainSpec::few_validators();
+
+        let mut builder = TestingBeaconStateBuilder::from_deterministic_keypairs(8, &spec);
+        let slot = (spec.genesis_epoch + 4).end_slot(spec.slots_per_epoch);
+        builder.teleport_to_slot(slot, &spec);
+        builder.insert_attestations(&spec);
+        let (state, _keypairs) = builder.build();
+
+        let bitmap = ShardCommitteeBitmap::new(&state, &spec).unwrap();
+
+        for a in state
+            .previous_epoch_attestations
+            .iter()
+            .chain(state.current_epoch_attestations.iter())
+        {
+            let participants = bitmap
+                .get_participants(&a.data, &a.aggregation_bitfield)
+                .unwrap();
+
+            assert_eq!(
+                participants,
+                get_attestation_participants(&state, &a.data, &a.aggregation_bitfield, &spec)
+                    .unwrap()
+            );
+            for &i in &participants {
+                assert!(bitmap.can_attest(a.data.shard, i));
+            }
+        }
+
+        let data = AttestationData {
+            slot: state.slot,
+            shard: spec.shard_count,
+            ..AttestationData::default()
+        };
+        assert_eq!(
+            bitmap.get_participants(&data, &Bitfield::new()),
+            Err(BeaconStateError::NoCommitteeForShard {
+                shard: spec.shard_count,
+                slot: state.slot,
+            })
+        );
+        assert!(!bitmap.can_attest(spec.shard_count, 0));
+    }
+}
@@ -1,5 +1,7 @@
 #![cfg(test)]
 use crate::per_epoch_processing;
+use crate::per_epoch_processing::winning_root::WinningRoot;
+use crate::per_epoch_processing::{finalizable_crosslinks, WinningRootHashSet};
 use env_logger::{Builder, Env};
 use types::test_utils::TestingBeaconStateBuilder;
 use types::*;
@@ -19,3 +21,49 @@ fn runs_without_error() {
 
     per_epoch_processing(&mut state, &spec).unwrap();
 }
+
+#[test]
+fn error_drops_effective_balance_cache() {
+    let spec = ChainSpec::few_validators();
+
+    let mut builder = TestingBeaconStateBuilder::from_deterministic_keypairs(8, &spec);
+    let target_slot = (spec.genesis_epoch + 4).end_slot(spec.slots_per_epoch);
+    builder.teleport_to_slot(target_slot, &spec);
+    builder.insert_attestations(&spec);
+    let (mut state, _keypairs) = builder.build();
+
+    // An attestation with a bitfield too long for its committee fails processing before rewards.
+    let len = state.previous_epoch_attestations[0]
+        .aggregation_bitfield
+        .len();
+    state.previous_epoch_attestations[0].aggregation_bitfield = Bitfield::from_elem(len + 16, true);
+
+    assert!(per_epoch_processing(&mut state, &spec).is_err());
+    assert!(!state.effective_balance_cache_is_current(&spec));
+}
+
+#[test]
+fn finalizable_crosslinks_requires_two_thirds() {
+    let winning_root = |byte: u8, total_attesting_balance: u64| WinningRoot {
+        crosslink_data_root: Hash256::from_slice(&[byte; 32]),
+        attesting_validator_indices: vec![],
+        total_attesting_balance,
+        committee_size: 3,
+        committee_total_balance: 3,
+        num_contributing_attestations: 1,
+    };
+
+    let mut winners = WinningRootHashSet::new();
+    winners.insert(0, winning_root(1, 3));
+    winners.insert(1, winning_root(2, 1));
+    winners.insert(2, winning_root(3, 2));
+    winners.insert(3, winning_root(4, 0));
+
+    assert_eq!(
+        finalizable_crosslinks(&winners),
+        vec![
+            (0, Hash256::from_slice(&[1; 32])),
+            (2, Hash256::from_slice(&[3; 32]))
+        ]
+    );
+}
@@ -96,7 +96,7 @@ pub fn update_validator_registry(
     for index in 0..state.validator_registry.len() {
         let not_activated =
             state.validator_registry[index].activation_epoch == spec.far_future_epoch;
-        let has_enough_balance = state.validator_balances[index] >= spec.max_deposit_amount;
+        let has_enough_balance = state.validator_balances()[index] >= spec.max_deposit_amount;
 
         if not_activated && has_enough_balance {
             // Check the balance churn would be within the allowance.
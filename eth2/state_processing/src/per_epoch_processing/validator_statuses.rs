@@ -277,12 +277,14 @@ impl ValidatorStatuses {
             for c in crosslink_committees_at_slot {
                 // If there was some winning crosslink root for the committee's shard.
                 if let Some(winning_root) = winning_roots.get(&c.shard) {
-                    let total_committee_balance = state.get_total_balance(&c.committee, spec)?;
                     for &validator_index in &winning_root.attesting_validator_indices {
                         // Take note of the balance information for the winning root, it will be
                         // used later to calculate rewards for that validator.
+                        //
+                        // The winning root records the balance of the previous epoch committee
+                        // for the shard, which is `c`.
                         self.statuses[validator_index].winning_root_info = Some(WinningRootInfo {
-                            total_committee_balance,
+                            total_committee_balance: winning_root.committee_total_balance,
                             total_attesting_balance: winning_root.total_attesting_balance,
                         })
                     }
@@ -1,13 +1,52 @@
+use super::attestation_data_cache::AttestationDataCache;
 use super::get_attestation_participants::get_attestation_participants;
-use std::collections::HashSet;
-use std::iter::FromIterator;
+use super::shard_committee_bitmap::ShardCommitteeBitmap;
+use serde_derive::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt;
 use types::*;
 
-#[derive(Clone)]
+/// The `crosslink_data_root` serializes as `0x`-prefixed hex.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct WinningRoot {
     pub crosslink_data_root: Hash256,
     pub attesting_validator_indices: Vec<usize>,
     pub total_attesting_balance: u64,
+    /// The number of validators in the committee for the shard.
+    pub committee_size: usize,
+    /// The sum of the effective balances of the committee for the shard.
+    pub committee_total_balance: u64,
+    /// The number of distinct attestations for the `crosslink_data_root` with at least one
+    /// participant.
+    pub num_contributing_attestations: usize,
+}
+
+/// Determines which `crosslink_data_root` is favoured when candidates have equal
+/// `total_attesting_balance`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TieBreak {
+    /// Favour the higher `crosslink_data_root`, as per spec v0.5.0.
+    HigherRoot,
+    /// Favour the lower `crosslink_data_root`.
+    LowerRoot,
+}
+
+impl Default for TieBreak {
+    fn default() -> Self {
+        TieBreak::HigherRoot
+    }
+}
+
+/// Compares two roots as the spec does, as 256-bit big-endian unsigned integers.
+///
+/// Equivalent to the `Ord` of `Hash256` (which compares the bytes lexicographically), this
+/// function makes the intended comparison explicit rather than relying on that equivalence.
+pub fn compare_roots(a: &Hash256, b: &Hash256) -> Ordering {
+    // The most significant byte of a big-endian integer is first, so comparing from the first
+    // byte compares the integers.
+    a.as_bytes().cmp(b.as_bytes())
 }
 
 impl WinningRoot {
@@ -18,18 +57,167 @@ impl WinningRoot {
     ///
     /// Spec v0.5.0
     pub fn is_better_than(&self, other: &Self) -> bool {
+        self.is_better_than_with(other, TieBreak::default())
+    }
+
+    /// Identical to `is_better_than`, however ties are broken according to `tie_break`.
+    pub fn is_better_than_with(&self, other: &Self, tie_break: TieBreak) -> bool {
         if self.total_attesting_balance > other.total_attesting_balance {
             true
         } else if self.total_attesting_balance == other.total_attesting_balance {
-            self.crosslink_data_root > other.crosslink_data_root
+            match tie_break {
+                TieBreak::HigherRoot => {
+                    compare_roots(&self.crosslink_data_root, &other.crosslink_data_root)
+                        == Ordering::Greater
+                }
+                TieBreak::LowerRoot => {
+                    compare_roots(&self.crosslink_data_root, &other.crosslink_data_root)
+                        == Ordering::Less
+                }
+            }
         } else {
             false
         }
     }
+
+    /// Updates `total_attesting_balance` for a change in the effective balance of
+    /// `validator_index` from `old_effective_balance` to `new_effective_balance`.
+    ///
+    /// Does nothing if the validator is not in `attesting_validator_indices`.
+    ///
+    /// Note: the `committee_total_balance` is not updated, the committee members are not known.
+    pub fn adjust_balance(
+        &mut self,
+        validator_index: usize,
+        old_effective_balance: u64,
+        new_effective_balance: u64,
+    ) {
+        // `attesting_validator_indices` is sorted.
+        if self
+            .attesting_validator_indices
+            .binary_search(&validator_index)
+            .is_ok()
+        {
+            self.total_attesting_balance = self
+                .total_attesting_balance
+                .saturating_sub(old_effective_balance)
+                + new_effective_balance;
+        }
+    }
+
+    /// Returns `BeaconStateError::InvalidWinningRoot` if `self` has a positive
+    /// `total_attesting_balance` but a zero `crosslink_data_root`, which likely indicates a bug in
+    /// the construction of `self`.
+    ///
+    /// Note: this is not applied by `winning_root`, phase 0 requires that all
+    /// `crosslink_data_root` values are zero. It is intended for use once shard blocks exist.
+    pub fn validate(&self) -> Result<(), BeaconStateError> {
+        if (self.total_attesting_balance > 0) && self.crosslink_data_root.is_zero() {
+            Err(BeaconStateError::InvalidWinningRoot)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Checks that `attesting_validator_indices` is sorted, contains no duplicates and that each
+    /// index is in a committee for `shard` during the previous or current epoch of `state`.
+    ///
+    /// This is relatively expensive, see `winning_root_checked`.
+    pub fn validate_indices(
+        &self,
+        state: &BeaconState,
+        shard: Shard,
+        spec: &ChainSpec,
+    ) -> Result<(), BeaconStateError> {
+        if let Some(pair) = self
+            .attesting_validator_indices
+            .windows(2)
+            .find(|pair| pair[0] >= pair[1])
+        {
+            return Err(BeaconStateError::DuplicateAttestingIndex(pair[1]));
+        }
+
+        let bitmap = ShardCommitteeBitmap::new(state, spec)?;
+
+        match self
+            .attesting_validator_indices
+            .iter()
+            .find(|&&i| !bitmap.can_attest(shard, i))
+        {
+            Some(&validator_index) => Err(BeaconStateError::AttesterNotInCommittee {
+                validator_index,
+                shard,
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Ord for WinningRoot {
+    /// Orders winning roots such that the "best" candidate is the greatest, consistent with
+    /// `is_better_than`.
+    ///
+    /// Compares `total_attesting_balance`, then `crosslink_data_root`.
+    /// The remaining fields are compared last, they only make the order total and never differ
+    /// between candidates with identical roots.
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.total_attesting_balance
+            .cmp(&other.total_attesting_balance)
+            .then_with(|| compare_roots(&self.crosslink_data_root, &other.crosslink_data_root))
+            .then_with(|| {
+                self.attesting_validator_indices
+                    .cmp(&other.attesting_validator_indices)
+            })
+            .then_with(|| self.committee_size.cmp(&other.committee_size))
+            .then_with(|| {
+                self.committee_total_balance
+                    .cmp(&other.committee_total_balance)
+            })
+            .then_with(|| {
+                self.num_contributing_attestations
+                    .cmp(&other.num_contributing_attestations)
+            })
+    }
+}
+
+impl PartialOrd for WinningRoot {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Debug for WinningRoot {
+    /// Formats the `crosslink_data_root` as `0x`-prefixed hex, rather than a raw byte array.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("WinningRoot")
+            .field(
+                "crosslink_data_root",
+                &format_args!("{:#x}", self.crosslink_data_root),
+            )
+            .field(
+                "attesting_validator_indices",
+                &self.attesting_validator_indices,
+            )
+            .field("total_attesting_balance", &self.total_attesting_balance)
+            .field("committee_size", &self.committee_size)
+            .field("committee_total_balance", &self.committee_total_balance)
+            .field(
+                "num_contributing_attestations",
+                &self.num_contributing_attestations,
+            )
+            .finish()
+    }
+}
+
+impl fmt::Display for WinningRoot {
+    /// Formats the `crosslink_data_root` as `0x`-prefixed hex, for logs.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:#x}", self.crosslink_data_root)
+    }
 }
 
 /// Returns the `crosslink_data_root` with the highest total attesting balance for the given shard.
-/// Breaks ties by favouring the smaller `crosslink_data_root` hash.
+/// Breaks ties by favouring the higher `crosslink_data_root` hash.
 ///
 /// The `WinningRoot` object also contains additional fields that are useful in later stages of
 /// per-epoch processing.
@@ -40,125 +228,3113 @@ pub fn winning_root(
     shard: u64,
     spec: &ChainSpec,
 ) -> Result<Option<WinningRoot>, BeaconStateError> {
-    let mut winning_root: Option<WinningRoot> = None;
+    winning_root_with_options(state, shard, &WinningRootOptions::default(), spec)
+}
 
-    let crosslink_data_roots: HashSet<Hash256> = HashSet::from_iter(
-        state
-            .previous_epoch_attestations
-            .iter()
-            .chain(state.current_epoch_attestations.iter())
-            .filter_map(|a| {
-                if is_eligible_for_winning_root(state, a, shard) {
-                    Some(a.data.crosslink_data_root)
-                } else {
-                    None
-                }
-            }),
-    );
+/// Identical to `winning_root`, however the winner is also checked with
+/// `WinningRoot::validate_indices` and any error is returned.
+///
+/// The check builds a `ShardCommitteeBitmap` for each call, it is intended for tests and for
+/// diagnosing suspect states rather than per-epoch processing.
+pub fn winning_root_checked(
+    state: &BeaconState,
+    shard: u64,
+    spec: &ChainSpec,
+) -> Result<Option<WinningRoot>, BeaconStateError> {
+    let winning_root = winning_root(state, shard, spec)?;
 
-    for crosslink_data_root in crosslink_data_roots {
-        let attesting_validator_indices =
-            get_attesting_validator_indices(state, shard, &crosslink_data_root, spec)?;
+    if let Some(winning_root) = &winning_root {
+        winning_root.validate_indices(state, shard, spec)?;
+    }
 
-        let total_attesting_balance: u64 =
-            attesting_validator_indices
-                .iter()
-                .try_fold(0_u64, |acc, i| {
-                    state
-                        .get_effective_balance(*i, spec)
-                        .and_then(|bal| Ok(acc + bal))
-                })?;
+    Ok(winning_root)
+}
 
-        let candidate = WinningRoot {
-            crosslink_data_root,
-            attesting_validator_indices,
-            total_attesting_balance,
-        };
+/// The fixed-point scale of `WinningRootOptions::inclusion_weight`, a weight of `1.0` is
+/// `INCLUSION_WEIGHT_SCALE`.
+pub const INCLUSION_WEIGHT_SCALE: u64 = 1_000_000_000;
 
-        if let Some(ref winner) = winning_root {
-            if candidate.is_better_than(&winner) {
-                winning_root = Some(candidate);
-            }
-        } else {
-            winning_root = Some(candidate);
-        }
+/// Options for `winning_root_with_options`, the default options are those of `winning_root`.
+///
+/// Other than `tie_break` and `bitmap`, the options change which attestations or balances are
+/// counted. They are intended for analysis, consensus code must use `winning_root`.
+#[derive(Clone, Copy, Default)]
+pub struct WinningRootOptions<'a> {
+    /// Determines the winner of candidates with equal balances.
+    pub tie_break: TieBreak,
+    /// If `Some`, attestations with a later `inclusion_slot` are ignored. Useful for analysing a
+    /// state part-way through an epoch.
+    pub max_inclusion_slot: Option<Slot>,
+    /// If `Some`, only attestations targeting this epoch are considered. In spec v0.5.0 the target
+    /// of an attestation is the epoch of its `data.slot`.
+    pub target_epoch: Option<Epoch>,
+    /// If `Some`, only attestations for which it returns `true` are considered. Allows callers to
+    /// exclude attestations they deem invalid (e.g., known slashable attestations).
+    pub filter: Option<&'a dyn Fn(&PendingAttestation) -> bool>,
+    /// If `true`, slashed validators are removed from the `attesting_validator_indices` (and
+    /// `total_attesting_balance`) of each candidate. A candidate whose attesters are all slashed
+    /// is not considered.
+    pub exclude_slashed: bool,
+    /// If `Some`, each attesting validator contributes `weight_fn(i)` to the
+    /// `total_attesting_balance` instead of its effective balance. E.g., `|_| 1` selects the root
+    /// with the most attesting validators. The `committee_total_balance` is always the effective
+    /// balance of the committee.
+    pub weight_fn: Option<&'a dyn Fn(usize) -> u64>,
+    /// If `Some`, the balance of each attesting validator is multiplied by
+    /// `inclusion_weight(inclusion_distance)`.
+    ///
+    /// The inclusion distance of a validator is `inclusion_slot - data.slot` of the attestation
+    /// which owns it (see `attesting_validator_inclusions`). Weights are clamped to `[0.0, 1.0]`
+    /// and converted to fixed-point (see `INCLUSION_WEIGHT_SCALE`) before being applied, so the
+    /// totals are summed as integers and are stable for a given `inclusion_weight`.
+    pub inclusion_weight: Option<&'a dyn Fn(u64) -> f64>,
+    /// If `Some`, attestation participants are found using the committees of the bitmap rather
+    /// than those of the state. The bitmap must have been built from the state, the result is
+    /// then unchanged.
+    pub bitmap: Option<&'a ShardCommitteeBitmap>,
+}
+
+impl<'a> WinningRootOptions<'a> {
+    /// Returns `true` if the attestation `a` is considered with these options.
+    fn selects(&self, a: &PendingAttestation, spec: &ChainSpec) -> bool {
+        self.max_inclusion_slot
+            .map_or(true, |max| a.inclusion_slot <= max)
+            && self.target_epoch.map_or(true, |epoch| {
+                a.data.slot.epoch(spec.slots_per_epoch) == epoch
+            })
+            && self.filter.map_or(true, |filter| filter(a))
     }
+}
 
-    Ok(winning_root)
+/// Identical to `winning_root`, with the given `options`.
+///
+/// See `WinningRootOptions`.
+pub fn winning_root_with_options(
+    state: &BeaconState,
+    shard: u64,
+    options: &WinningRootOptions,
+    spec: &ChainSpec,
+) -> Result<Option<WinningRoot>, BeaconStateError> {
+    let previous_epoch_attestations = state.previous_epoch_attestations_safe(spec);
+    let current_epoch_attestations = &state.current_epoch_attestations;
+
+    let candidates = match options.bitmap {
+        Some(bitmap) => winning_root_candidates(
+            state,
+            shard,
+            previous_epoch_attestations,
+            current_epoch_attestations,
+            &BitmapResolver {
+                state,
+                bitmap,
+                spec,
+            },
+            options,
+            spec,
+        )?,
+        None => winning_root_candidates(
+            state,
+            shard,
+            previous_epoch_attestations,
+            current_epoch_attestations,
+            &(state, spec),
+            options,
+            spec,
+        )?,
+    };
+
+    Ok(candidates
+        .into_iter()
+        .fold(None, |best, candidate| match best {
+            Some(best) if !candidate.is_better_than_with(&best, options.tie_break) => Some(best),
+            _ => Some(candidate),
+        }))
 }
 
-/// Returns `true` if pending attestation `a` is eligible to become a winning root.
+/// Identical to `winning_root`, however attestation participants are read from (and added to)
+/// the given `cache`.
+///
+/// Sharing a cache between calls for different shards of the same `state` avoids recomputing the
+/// participants of an `AttestationData` more than once.
 ///
 /// Spec v0.5.0
-fn is_eligible_for_winning_root(state: &BeaconState, a: &PendingAttestation, shard: Shard) -> bool {
-    if shard >= state.latest_crosslinks.len() as u64 {
-        return false;
-    }
+pub fn winning_root_with_cache(
+    state: &BeaconState,
+    shard: u64,
+    cache: &mut AttestationDataCache,
+    spec: &ChainSpec,
+) -> Result<Option<WinningRoot>, BeaconStateError> {
+    winning_root_for_attestations(
+        state,
+        shard,
+        state.previous_epoch_attestations_safe(spec),
+        &state.current_epoch_attestations,
+        cache,
+        spec,
+    )
+}
 
-    a.data.previous_crosslink == state.latest_crosslinks[shard as usize]
+/// Identical to `winning_root`, however the winner is pushed onto `arena` and its index in `arena`
+/// is returned.
+///
+/// Returns `Ok(None)` (leaving `arena` unchanged) if there is no winner. Allows callers keeping
+/// the winners of many shards to manage their allocation, rather than holding each `WinningRoot`
+/// separately.
+///
+/// Spec v0.5.0
+pub fn winning_root_in_arena(
+    state: &BeaconState,
+    shard: u64,
+    arena: &mut Vec<WinningRoot>,
+    spec: &ChainSpec,
+) -> Result<Option<usize>, BeaconStateError> {
+    Ok(winning_root(state, shard, spec)?.map(|winner| {
+        arena.push(winner);
+        arena.len() - 1
+    }))
 }
 
-/// Returns all indices which voted for a given crosslink. Does not contain duplicates.
+/// Returns the winning root for `shard`, considering only the given attestations.
+///
+/// The attestations should be from `state` (or be consistent with it), they are used in place of
+/// `state.previous_epoch_attestations` and `state.current_epoch_attestations`. The `cache` should
+/// only ever be used with the same set of attestations.
+///
+/// Returns `BeaconStateError::AttestationOutOfEpoch` if any attestation is not in the previous or
+/// current epoch (respectively) of `state`. This is a cheap way to detect attestations which were
+/// taken from a different state.
 ///
 /// Spec v0.5.0
-fn get_attesting_validator_indices(
+pub fn winning_root_for_attestations(
     state: &BeaconState,
     shard: u64,
-    crosslink_data_root: &Hash256,
+    previous_epoch_attestations: &[PendingAttestation],
+    current_epoch_attestations: &[PendingAttestation],
+    cache: &mut AttestationDataCache,
     spec: &ChainSpec,
-) -> Result<Vec<usize>, BeaconStateError> {
-    let mut indices = vec![];
+) -> Result<Option<WinningRoot>, BeaconStateError> {
+    let candidates = winning_root_candidates(
+        state,
+        shard,
+        previous_epoch_attestations,
+        current_epoch_attestations,
+        &CachedResolver {
+            state,
+            previous_epoch_attestations,
+            current_epoch_attestations,
+            cache: RefCell::new(cache),
+            spec,
+        },
+        &WinningRootOptions::default(),
+        spec,
+    )?;
+
+    // The `Ord` implementation selects the same winner as `WinningRoot::is_better_than`.
+    Ok(candidates.into_iter().max())
+}
+
+/// Converts `weight` to fixed-point, clamped to `[0, INCLUSION_WEIGHT_SCALE]`. `NaN` is zero.
+fn fixed_point_weight(weight: f64) -> u64 {
+    if weight.is_nan() || weight <= 0.0 {
+        0
+    } else if weight >= 1.0 {
+        INCLUSION_WEIGHT_SCALE
+    } else {
+        (weight * INCLUSION_WEIGHT_SCALE as f64).round() as u64
+    }
+}
 
-    for a in state
-        .current_epoch_attestations
+/// Identical to `winning_root`, however the `(crosslink_data_root, total_attesting_balance)` of
+/// every candidate (including the winner) is also returned.
+///
+/// The candidates are sorted from best to worst, in the order of `WinningRoot::is_better_than`.
+/// Useful for diagnosing why an expected crosslink did not win.
+pub fn winning_root_verbose(
+    state: &BeaconState,
+    shard: u64,
+    spec: &ChainSpec,
+) -> Result<Option<(WinningRoot, Vec<(Hash256, u64)>)>, BeaconStateError> {
+    let mut candidates = winning_root_candidates(
+        state,
+        shard,
+        state.previous_epoch_attestations_safe(spec),
+        &state.current_epoch_attestations,
+        &(state, spec),
+        &WinningRootOptions::default(),
+        spec,
+    )?;
+
+    candidates.sort_unstable_by(|a, b| b.cmp(a));
+
+    let roots = candidates
         .iter()
-        .chain(state.previous_epoch_attestations.iter())
-    {
-        if (a.data.shard == shard) && (a.data.crosslink_data_root == *crosslink_data_root) {
-            indices.append(&mut get_attestation_participants(
+        .map(|c| (c.crosslink_data_root, c.total_attesting_balance))
+        .collect();
+
+    Ok(candidates.into_iter().next().map(|winner| (winner, roots)))
+}
+
+/// Identical to `winning_root`, however the `(crosslink_data_root, total_attesting_balance)` of the
+/// second-best candidate is also returned, or `None` if there is only one candidate.
+///
+/// The runner-up is chosen with the same ordering (and tie-break) as the winner, see
+/// `winning_root_verbose`. Useful when investigating a crosslink reorg.
+pub fn winning_root_with_runner_up(
+    state: &BeaconState,
+    shard: u64,
+    spec: &ChainSpec,
+) -> Result<Option<(WinningRoot, Option<(Hash256, u64)>)>, BeaconStateError> {
+    Ok(winning_root_verbose(state, shard, spec)?
+        .map(|(winner, candidates)| (winner, candidates.get(1).cloned())))
+}
+
+/// Returns the `total_attesting_balance` of every candidate `crosslink_data_root` for `shard`,
+/// without selecting a winner.
+///
+/// Intended for exporting (e.g., as JSON) and comparing the tallies of different implementations.
+/// The map is empty if the shard has no candidates.
+pub fn winning_root_tally(
+    state: &BeaconState,
+    shard: u64,
+    spec: &ChainSpec,
+) -> Result<HashMap<Hash256, u64>, BeaconStateError> {
+    let candidates = winning_root_candidates(
+        state,
+        shard,
+        state.previous_epoch_attestations_safe(spec),
+        &state.current_epoch_attestations,
+        &(state, spec),
+        &WinningRootOptions::default(),
+        spec,
+    )?;
+
+    Ok(candidates
+        .into_iter()
+        .map(|c| (c.crosslink_data_root, c.total_attesting_balance))
+        .collect())
+}
+
+/// Returns the winning root of each shard which was attested to in `epoch` or the epoch prior,
+/// considering only the `included_attestations`.
+///
+/// Intended for recomputing the winning roots of some past epoch for chain analysis. The
+/// `state_at_epoch` provides the committees and must have `epoch` as its current epoch, however
+/// its own pending attestations are ignored. Each of the `included_attestations` is assigned to
+/// the previous or current epoch by its slot, attestations from any other epoch are ignored.
+///
+/// Returns `BeaconStateError::EpochOutOfBounds` if `epoch` is not the current epoch of
+/// `state_at_epoch`.
+pub fn historical_winning_roots(
+    state_at_epoch: &BeaconState,
+    included_attestations: &[PendingAttestation],
+    epoch: Epoch,
+    spec: &ChainSpec,
+) -> Result<HashMap<Shard, WinningRoot>, BeaconStateError> {
+    let state = state_at_epoch;
+
+    if state.current_epoch(spec) != epoch {
+        return Err(BeaconStateError::EpochOutOfBounds);
+    }
+
+    let attestation_epoch = |a: &PendingAttestation| a.data.slot.epoch(spec.slots_per_epoch);
+
+    let current_epoch_attestations: Vec<PendingAttestation> = included_attestations
+        .iter()
+        .filter(|a| attestation_epoch(a) == epoch)
+        .cloned()
+        .collect();
+    // At genesis the previous and current epochs are the same, there are no previous attestations.
+    let previous_epoch_attestations: Vec<PendingAttestation> = included_attestations
+        .iter()
+        .filter(|a| (epoch > spec.genesis_epoch) && (attestation_epoch(a) == epoch - 1))
+        .cloned()
+        .collect();
+
+    let shards: HashSet<Shard> = previous_epoch_attestations
+        .iter()
+        .chain(current_epoch_attestations.iter())
+        .map(|a| a.data.shard)
+        .collect();
+
+    let cache = &mut AttestationDataCache::new();
+    let mut winning_roots = HashMap::new();
+
+    for shard in shards {
+        if let Some(winning_root) = winning_root_for_attestations(
+            state,
+            shard,
+            &previous_epoch_attestations,
+            &current_epoch_attestations,
+            cache,
+            spec,
+        )? {
+            winning_roots.insert(shard, winning_root);
+        }
+    }
+
+    Ok(winning_roots)
+}
+
+/// Identical to `winning_root`, however the winner is found from the given `attestations` (from the
+/// previous and current epochs of `state`) and the indices of the `attestations` which contributed
+/// to the winner are returned.
+///
+/// The contributing attestations are those for the winning `crosslink_data_root` of `shard` with
+/// at least one participant, in ascending order.
+///
+/// Returns `BeaconStateError::AttestationOutOfEpoch` if any attestation is not in the previous or
+/// current epoch of `state`.
+pub fn winning_root_owned(
+    state: &BeaconState,
+    shard: u64,
+    attestations: &[PendingAttestation],
+    spec: &ChainSpec,
+) -> Result<Option<Vec<usize>>, BeaconStateError> {
+    let previous_epoch = state.previous_epoch(spec);
+    let current_epoch = state.current_epoch(spec);
+
+    let mut previous_epoch_attestations = vec![];
+    let mut current_epoch_attestations = vec![];
+    for a in attestations {
+        let epoch = a.data.slot.epoch(spec.slots_per_epoch);
+
+        if epoch == current_epoch {
+            current_epoch_attestations.push(a.clone());
+        } else if epoch == previous_epoch {
+            previous_epoch_attestations.push(a.clone());
+        } else {
+            return Err(BeaconStateError::AttestationOutOfEpoch {
+                slot: a.data.slot,
+                epoch: current_epoch,
+            });
+        }
+    }
+
+    let winner = match winning_root_for_attestations(
+        state,
+        shard,
+        &previous_epoch_attestations,
+        &current_epoch_attestations,
+        &mut AttestationDataCache::new(),
+        spec,
+    )? {
+        Some(winner) => winner,
+        None => return Ok(None),
+    };
+
+    let mut indices = vec![];
+    for (i, a) in attestations.iter().enumerate() {
+        if (a.data.shard == shard)
+            && (a.data.crosslink_data_root == winner.crosslink_data_root)
+            && has_participants(a)
+            && is_eligible_for_winning_root(state, a, shard)
+        {
+            indices.push(i);
+        }
+    }
+
+    Ok(Some(indices))
+}
+
+/// The attesters of a `WinningRoot`, partitioned by the epoch of their attestations.
+///
+/// A validator may be in both sets (e.g., if it attested to the root in both epochs).
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct EpochAttesters {
+    /// Sorted, without duplicates.
+    pub previous_epoch_attesters: Vec<usize>,
+    /// Sorted, without duplicates.
+    pub current_epoch_attesters: Vec<usize>,
+}
+
+/// Identical to `winning_root`, however the attesters of the winner are also returned, partitioned
+/// by the epoch of their attestations.
+///
+/// The `attesting_validator_indices` (and `total_attesting_balance`) of the winner are the union
+/// of both sets.
+pub fn winning_root_with_epoch_attesters(
+    state: &BeaconState,
+    shard: u64,
+    spec: &ChainSpec,
+) -> Result<Option<(WinningRoot, EpochAttesters)>, BeaconStateError> {
+    let winner = match winning_root(state, shard, spec)? {
+        Some(winner) => winner,
+        None => return Ok(None),
+    };
+
+    let attesters = |attestations: &[PendingAttestation]| -> Result<Vec<usize>, BeaconStateError> {
+        let attestations: Vec<&PendingAttestation> =
+            eligible_attestations(state, shard, attestations.iter())
+                .into_iter()
+                .filter(|a| a.data.crosslink_data_root == winner.crosslink_data_root)
+                .collect();
+
+        get_attesting_validator_indices(&(state, spec), &attestations)
+    };
+
+    let epoch_attesters = EpochAttesters {
+        previous_epoch_attesters: attesters(state.previous_epoch_attestations_safe(spec))?,
+        current_epoch_attesters: attesters(&state.current_epoch_attestations)?,
+    };
+
+    Ok(Some((winner, epoch_attesters)))
+}
+
+/// Returns the shards of the crosslink committees of `epoch`, in ascending order.
+///
+/// The `epoch` must be the previous, current or next epoch of `state` (see
+/// `BeaconState::get_crosslink_committees_at_slot`).
+pub fn active_shards_for_epoch(
+    state: &BeaconState,
+    epoch: Epoch,
+    spec: &ChainSpec,
+) -> Result<Vec<Shard>, BeaconStateError> {
+    let mut shards = vec![];
+    for slot in epoch.slot_iter(spec.slots_per_epoch) {
+        for c in state.get_crosslink_committees_at_slot(slot, spec)? {
+            shards.push(c.shard);
+        }
+    }
+
+    shards.sort_unstable();
+    shards.dedup();
+
+    Ok(shards)
+}
+
+/// Returns the winning root of each shard with a crosslink committee in the previous or current
+/// epoch of `state` (see `active_shards_for_epoch`), a shard without a winning root has a `None`
+/// entry.
+///
+/// If `shards` is supplied, there is an entry for exactly the listed shards instead. E.g., for
+/// representing every shard of some committees, whether or not they were attested to.
+pub fn all_winning_roots(
+    state: &BeaconState,
+    shards: Option<&[Shard]>,
+    spec: &ChainSpec,
+) -> Result<HashMap<Shard, Option<WinningRoot>>, BeaconStateError> {
+    let shards = winning_root_shards(state, shards, spec)?;
+
+    let cache = &mut AttestationDataCache::new();
+    let mut winning_roots = HashMap::with_capacity(shards.len());
+
+    for shard in shards {
+        winning_roots.insert(shard, winning_root_with_cache(state, shard, cache, spec)?);
+    }
+
+    Ok(winning_roots)
+}
+
+/// Identical to `all_winning_roots`, however an error computing the winning root of a shard is
+/// recorded in the entry for that shard, rather than returned.
+///
+/// Useful for analysing possibly-corrupt states, where the failure of one shard should not hide
+/// the results of the others. An error is only returned if the shards of `state` cannot be
+/// enumerated (i.e., if `shards` is `None`).
+pub fn try_all_winning_roots(
+    state: &BeaconState,
+    shards: Option<&[Shard]>,
+    spec: &ChainSpec,
+) -> Result<HashMap<Shard, Result<Option<WinningRoot>, BeaconStateError>>, BeaconStateError> {
+    let shards = winning_root_shards(state, shards, spec)?;
+
+    let cache = &mut AttestationDataCache::new();
+
+    Ok(shards
+        .into_iter()
+        .map(|shard| (shard, winning_root_with_cache(state, shard, cache, spec)))
+        .collect())
+}
+
+/// Returns the distinct `shards`, or the shards with a committee in the previous or current epoch
+/// of `state` if `shards` is `None`.
+fn winning_root_shards(
+    state: &BeaconState,
+    shards: Option<&[Shard]>,
+    spec: &ChainSpec,
+) -> Result<HashSet<Shard>, BeaconStateError> {
+    Ok(match shards {
+        Some(shards) => shards.iter().cloned().collect(),
+        None => {
+            let mut shards = active_shards_for_epoch(state, state.previous_epoch(spec), spec)?;
+            shards.append(&mut active_shards_for_epoch(
                 state,
-                &a.data,
-                &a.aggregation_bitfield,
+                state.current_epoch(spec),
                 spec,
             )?);
+            shards.into_iter().collect()
         }
-    }
+    })
+}
 
-    // Sort the list (required for dedup). "Unstable" means the sort may re-order equal elements,
-    // this causes no issue here.
-    //
-    // These sort + dedup ops are potentially good CPU time optimisation targets.
-    indices.sort_unstable();
-    // Remove all duplicate indices (requires a sorted list).
-    indices.dedup();
+/// Statistics of the winning roots of the shards of the previous and current epochs of a state.
+///
+/// See `all_winning_roots`.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct EpochCrosslinkStats {
+    /// The number of shards with a winning root.
+    pub shards_with_winner: usize,
+    /// The sum of the `total_attesting_balance` of each winning root.
+    pub total_attesting_balance: u64,
+    /// The `(shard, margin)` of the shard with the smallest difference in balance between its top
+    /// two candidates, preferring the lowest shard. `None` if no shard has two candidates.
+    pub closest_contest: Option<(Shard, u64)>,
+}
 
-    Ok(indices)
+impl EpochCrosslinkStats {
+    /// Computes the statistics for `state`.
+    pub fn new(state: &BeaconState, spec: &ChainSpec) -> Result<Self, BeaconStateError> {
+        // Visited in ascending order, so the lowest shard is preferred for the `closest_contest`.
+        let shards: BTreeSet<Shard> = winning_root_shards(state, None, spec)?
+            .into_iter()
+            .collect();
+
+        let mut stats = Self::default();
+
+        for shard in shards {
+            // The candidates are sorted, the first is the winner.
+            let (winner, candidates) = match winning_root_verbose(state, shard, spec)? {
+                Some(verbose) => verbose,
+                None => continue,
+            };
+
+            stats.shards_with_winner += 1;
+            stats.total_attesting_balance += winner.total_attesting_balance;
+
+            if candidates.len() > 1 {
+                let margin = candidates[0].1 - candidates[1].1;
+
+                match stats.closest_contest {
+                    Some((_, closest)) if closest <= margin => {}
+                    _ => stats.closest_contest = Some((shard, margin)),
+                }
+            }
+        }
+
+        Ok(stats)
+    }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// A difference between the winning roots of a shard, as computed by two implementations (e.g.,
+/// this one and another client).
+///
+/// See `diff_winning_roots`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CrosslinkDiff {
+    pub shard: Shard,
+    /// Our winning `crosslink_data_root`, `None` if we have no winning root.
+    pub our_root: Option<Hash256>,
+    /// Their winning `crosslink_data_root`, `None` if they have no winning root.
+    pub their_root: Option<Hash256>,
+    /// Our `total_attesting_balance`, zero if we have no winning root.
+    pub our_balance: u64,
+    /// Their `total_attesting_balance`, zero if they have no winning root.
+    pub their_balance: u64,
+    /// The attesting validators of our winning root which are not attesters of theirs.
+    pub attesters_only_ours: Vec<usize>,
+    /// The attesting validators of their winning root which are not attesters of ours.
+    pub attesters_only_theirs: Vec<usize>,
+}
 
-    #[test]
-    fn is_better_than() {
-        let worse = WinningRoot {
-            crosslink_data_root: Hash256::from_slice(&[1; 32]),
-            attesting_validator_indices: vec![],
-            total_attesting_balance: 42,
-        };
+/// Compares two sets of winning roots (e.g., the output of `all_winning_roots` from this and
+/// another implementation), returning a `CrosslinkDiff` for each shard where the winning root,
+/// its balance or its attesters differ.
+///
+/// A shard missing from one map is equivalent to a `None` entry. The diffs are sorted by shard.
+pub fn diff_winning_roots(
+    ours: &HashMap<Shard, Option<WinningRoot>>,
+    theirs: &HashMap<Shard, Option<WinningRoot>>,
+) -> Vec<CrosslinkDiff> {
+    let shards: BTreeSet<Shard> = ours.keys().chain(theirs.keys()).cloned().collect();
 
-        let better = WinningRoot {
-            crosslink_data_root: Hash256::from_slice(&[2; 32]),
-            ..worse.clone()
-        };
+    let attesters = |winning_root: Option<&WinningRoot>| -> BTreeSet<usize> {
+        winning_root
+            .map(|w| w.attesting_validator_indices.iter().cloned().collect())
+            .unwrap_or_default()
+    };
 
-        assert!(better.is_better_than(&worse));
+    shards
+        .into_iter()
+        .filter_map(|shard| {
+            let our_winner = ours.get(&shard).and_then(Option::as_ref);
+            let their_winner = theirs.get(&shard).and_then(Option::as_ref);
 
-        let better = WinningRoot {
-            total_attesting_balance: worse.total_attesting_balance + 1,
-            ..worse.clone()
-        };
+            let our_attesters = attesters(our_winner);
+            let their_attesters = attesters(their_winner);
+
+            let diff = CrosslinkDiff {
+                shard,
+                our_root: our_winner.map(|w| w.crosslink_data_root),
+                their_root: their_winner.map(|w| w.crosslink_data_root),
+                our_balance: our_winner.map_or(0, |w| w.total_attesting_balance),
+                their_balance: their_winner.map_or(0, |w| w.total_attesting_balance),
+                attesters_only_ours: our_attesters
+                    .difference(&their_attesters)
+                    .cloned()
+                    .collect(),
+                attesters_only_theirs: their_attesters
+                    .difference(&our_attesters)
+                    .cloned()
+                    .collect(),
+            };
+
+            if diff.our_root == diff.their_root
+                && diff.our_balance == diff.their_balance
+                && diff.attesters_only_ours.is_empty()
+                && diff.attesters_only_theirs.is_empty()
+            {
+                None
+            } else {
+                Some(diff)
+            }
+        })
+        .collect()
+}
+
+/// Returns a `WinningRoot` for each eligible `crosslink_data_root` of `shard`, in ascending order
+/// of `crosslink_data_root`, counted as per the `options`.
+///
+/// See `winning_root_for_attestations`.
+fn winning_root_candidates<R>(
+    state: &BeaconState,
+    shard: u64,
+    previous_epoch_attestations: &[PendingAttestation],
+    current_epoch_attestations: &[PendingAttestation],
+    resolver: &R,
+    options: &WinningRootOptions,
+    spec: &ChainSpec,
+) -> Result<Vec<WinningRoot>, BeaconStateError>
+where
+    R: ParticipantResolver + ?Sized,
+{
+    verify_attestation_epochs(
+        previous_epoch_attestations,
+        state.previous_epoch(spec),
+        spec,
+    )?;
+    verify_attestation_epochs(current_epoch_attestations, state.current_epoch(spec), spec)?;
+
+    // The overlap check is quadratic, so it is only asserted in debug builds. It never changes the
+    // result, which must not depend on the build profile.
+    debug_assert_eq!(
+        validate_inputs(
+            state,
+            previous_epoch_attestations,
+            current_epoch_attestations,
+            spec
+        ),
+        Ok(())
+    );
+
+    let attestations: Vec<&PendingAttestation> = eligible_attestations(
+        state,
+        shard,
+        previous_epoch_attestations
+            .iter()
+            .chain(current_epoch_attestations.iter()),
+    )
+    .into_iter()
+    .filter(|a| options.selects(a, spec))
+    .collect();
+
+    // Many shards receive no attestations, return early for these.
+    if attestations.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let committee = shard_committee(state, shard, spec)?;
+    let is_counted = |i: usize| !(options.exclude_slashed && state.validator_registry[i].slashed);
+
+    accumulate_candidates(
+        resolver,
+        &attestations,
+        &committee.committee,
+        options,
+        &is_counted,
+    )
+}
+
+/// Returns a `WinningRoot` for each `crosslink_data_root` of the `attestations`, in ascending order
+/// of `crosslink_data_root`. Only the attesters for which `is_counted` returns `true` are counted,
+/// with the `weight_fn` and `inclusion_weight` of `options`.
+///
+/// This is the tally shared by all `winning_root` functions. The `attestations` must be for the
+/// shard of `committee`, eligible and have participants, no further checks are made.
+fn accumulate_candidates<R>(
+    resolver: &R,
+    attestations: &[&PendingAttestation],
+    committee: &[usize],
+    options: &WinningRootOptions,
+    is_counted: &dyn Fn(usize) -> bool,
+) -> Result<Vec<WinningRoot>, BeaconStateError>
+where
+    R: ParticipantResolver + ?Sized,
+{
+    // Keyed by `crosslink_data_root`, so candidates are built and returned in a deterministic
+    // order regardless of the order of the attestations.
+    let mut roots: BTreeMap<Hash256, Vec<&PendingAttestation>> = BTreeMap::new();
+    for a in attestations {
+        roots
+            .entry(a.data.crosslink_data_root)
+            .or_insert_with(Vec::new)
+            .push(a);
+    }
+
+    if roots.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let committee_total_balance = committee.iter().try_fold(0_u64, |acc, &i| {
+        resolver.effective_balance(i).and_then(|bal| Ok(acc + bal))
+    })?;
+
+    let mut candidates = Vec::with_capacity(roots.len());
+
+    for (crosslink_data_root, attestations) in roots {
+        let mut attesting_validator_indices =
+            get_attesting_validator_indices(resolver, &attestations)?;
+        attesting_validator_indices.retain(|&i| is_counted(i));
+
+        // A candidate without any counted attesters is not considered.
+        if attesting_validator_indices.is_empty() {
+            continue;
+        }
+
+        let inclusion_distances = match options.inclusion_weight {
+            Some(_) => inclusion_distances(resolver, &attestations)?,
+            None => HashMap::new(),
+        };
+
+        let mut total_attesting_balance = 0;
+        for &i in &attesting_validator_indices {
+            let balance = match options.weight_fn {
+                Some(weight_fn) => weight_fn(i),
+                None => resolver.effective_balance(i)?,
+            };
+
+            total_attesting_balance += match options.inclusion_weight {
+                Some(inclusion_weight) => {
+                    let weight = fixed_point_weight(inclusion_weight(inclusion_distances[&i]));
+                    (u128::from(balance) * u128::from(weight) / u128::from(INCLUSION_WEIGHT_SCALE))
+                        as u64
+                }
+                None => balance,
+            };
+        }
+
+        candidates.push(WinningRoot {
+            crosslink_data_root,
+            attesting_validator_indices,
+            total_attesting_balance,
+            committee_size: committee.len(),
+            committee_total_balance,
+            num_contributing_attestations: attestations.len(),
+        });
+    }
+
+    Ok(candidates)
+}
+
+/// Returns the inclusion distance (`inclusion_slot - data.slot`) of the attestation which owns each
+/// participant of the `attestations`.
+///
+/// The owner is the attestation with the earliest `inclusion_slot`, ties are broken by the order of
+/// the `attestations` (see `attesting_validator_inclusions`).
+fn inclusion_distances<R>(
+    resolver: &R,
+    attestations: &[&PendingAttestation],
+) -> Result<HashMap<usize, u64>, BeaconStateError>
+where
+    R: ParticipantResolver + ?Sized,
+{
+    // Maps each validator to the `(inclusion_slot, inclusion_distance)` of its owner.
+    let mut owners: HashMap<usize, (Slot, u64)> = HashMap::new();
+
+    for a in attestations {
+        let distance = a
+            .inclusion_slot
+            .as_u64()
+            .saturating_sub(a.data.slot.as_u64());
+        let owner = (a.inclusion_slot, distance);
+
+        for validator_index in resolver.participants(&a.data, &a.aggregation_bitfield)? {
+            let current = owners.entry(validator_index).or_insert(owner);
+            if owner.0 < current.0 {
+                *current = owner;
+            }
+        }
+    }
+
+    Ok(owners
+        .into_iter()
+        .map(|(validator_index, (_, distance))| (validator_index, distance))
+        .collect())
+}
+
+/// Resolves the participants of attestations and the balances of validators, decoupling the
+/// selection of a winning root from `BeaconState` (e.g., for mocks or other state backends).
+///
+/// `winning_root` uses the implementation for `(&BeaconState, &ChainSpec)`. See
+/// `winning_root_with_resolver`.
+pub trait ParticipantResolver {
+    /// Returns the indices of the validators marked in `bitfield` for the committee of
+    /// `attestation_data`.
+    fn participants(
+        &self,
+        attestation_data: &AttestationData,
+        bitfield: &Bitfield,
+    ) -> Result<Vec<usize>, BeaconStateError>;
+
+    /// Returns the effective balance of `validator_index`.
+    fn effective_balance(&self, validator_index: usize) -> Result<u64, BeaconStateError>;
+}
+
+impl<'a> ParticipantResolver for (&'a BeaconState, &'a ChainSpec) {
+    fn participants(
+        &self,
+        attestation_data: &AttestationData,
+        bitfield: &Bitfield,
+    ) -> Result<Vec<usize>, BeaconStateError> {
+        get_attestation_participants(self.0, attestation_data, bitfield, self.1)
+    }
+
+    fn effective_balance(&self, validator_index: usize) -> Result<u64, BeaconStateError> {
+        self.0.get_effective_balance(validator_index, self.1)
+    }
+}
+
+/// Resolves participants with an `AttestationDataCache` of the given attestations, and balances
+/// from `state`.
+///
+/// The cache merges the participants of all attestations sharing an `AttestationData`, these are
+/// returned regardless of `bitfield`. All such attestations are counted towards the same root, so
+/// the `attesting_validator_indices` are unchanged. However the attestations cannot be told apart,
+/// so it must only be used with the default `WinningRootOptions`.
+struct CachedResolver<'a> {
+    state: &'a BeaconState,
+    previous_epoch_attestations: &'a [PendingAttestation],
+    current_epoch_attestations: &'a [PendingAttestation],
+    cache: RefCell<&'a mut AttestationDataCache>,
+    spec: &'a ChainSpec,
+}
+
+impl<'a> ParticipantResolver for CachedResolver<'a> {
+    fn participants(
+        &self,
+        attestation_data: &AttestationData,
+        _bitfield: &Bitfield,
+    ) -> Result<Vec<usize>, BeaconStateError> {
+        let attestations = self
+            .previous_epoch_attestations
+            .iter()
+            .chain(self.current_epoch_attestations.iter());
+
+        self.cache
+            .borrow_mut()
+            .get_participants(self.state, attestation_data, attestations, self.spec)
+            .map(<[usize]>::to_vec)
+    }
+
+    fn effective_balance(&self, validator_index: usize) -> Result<u64, BeaconStateError> {
+        self.state.get_effective_balance(validator_index, self.spec)
+    }
+}
+
+/// Resolves participants with the committees of a `ShardCommitteeBitmap`, and balances from
+/// `state`.
+struct BitmapResolver<'a> {
+    state: &'a BeaconState,
+    bitmap: &'a ShardCommitteeBitmap,
+    spec: &'a ChainSpec,
+}
+
+impl<'a> ParticipantResolver for BitmapResolver<'a> {
+    fn participants(
+        &self,
+        attestation_data: &AttestationData,
+        bitfield: &Bitfield,
+    ) -> Result<Vec<usize>, BeaconStateError> {
+        self.bitmap.get_participants(attestation_data, bitfield)
+    }
+
+    fn effective_balance(&self, validator_index: usize) -> Result<u64, BeaconStateError> {
+        self.state.get_effective_balance(validator_index, self.spec)
+    }
+}
+
+/// Returns the winning root of `attestations` for `shard`, where `committee` is the crosslink
+/// committee of the shard, using `resolver` rather than a `BeaconState`.
+///
+/// Attestations are matched to `shard` by their `data.shard` and are assumed to be eligible (no
+/// `previous_crosslink` check is made). Ties are broken as per `winning_root`. Given the eligible
+/// attestations of a state and `(&state, &spec)`, the result is identical to `winning_root`.
+pub fn winning_root_with_resolver<R>(
+    resolver: &R,
+    shard: Shard,
+    attestations: &[PendingAttestation],
+    committee: &[usize],
+) -> Result<Option<WinningRoot>, BeaconStateError>
+where
+    R: ParticipantResolver + ?Sized,
+{
+    let attestations: Vec<&PendingAttestation> = attestations
+        .iter()
+        .filter(|a| a.data.shard == shard && has_participants(a))
+        .collect();
+
+    let candidates = accumulate_candidates(
+        resolver,
+        &attestations,
+        committee,
+        &WinningRootOptions::default(),
+        &|_| true,
+    )?;
+
+    Ok(candidates.into_iter().max())
+}
+
+/// Caches the winning root of each shard for some `BeaconState`.
+///
+/// Results are keyed by the `canonical_root` of the state. Any change to the state (e.g., a new
+/// attestation, a modified balance or a new crosslink) changes its root and invalidates all cached
+/// results. Computing the root hashes the whole state, so the cache only pays off when the winning
+/// root of a shard is requested more than once.
+#[derive(Debug, Default, Clone)]
+pub struct WinningRootCache {
+    state_root: Option<Hash256>,
+    winning_roots: HashMap<Shard, Option<WinningRoot>>,
+    attestation_data_cache: AttestationDataCache,
+}
+
+impl WinningRootCache {
+    /// Returns an empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of shards with a cached result.
+    pub fn len(&self) -> usize {
+        self.winning_roots.len()
+    }
+
+    /// Returns `true` if there are no cached results.
+    pub fn is_empty(&self) -> bool {
+        self.winning_roots.is_empty()
+    }
+
+    /// Returns the winning root for `shard`, reading from the cache if `state` is unchanged since
+    /// the previous call.
+    ///
+    /// See `winning_root`.
+    pub fn winning_root(
+        &mut self,
+        state: &BeaconState,
+        shard: Shard,
+        spec: &ChainSpec,
+    ) -> Result<Option<WinningRoot>, BeaconStateError> {
+        let state_root = state.canonical_root();
+
+        if self.state_root != Some(state_root) {
+            *self = Self {
+                state_root: Some(state_root),
+                ..Self::default()
+            };
+        }
+
+        if let Some(winning_root) = self.winning_roots.get(&shard) {
+            return Ok(winning_root.clone());
+        }
+
+        let winning_root =
+            winning_root_with_cache(state, shard, &mut self.attestation_data_cache, spec)?;
+        self.winning_roots.insert(shard, winning_root.clone());
+
+        Ok(winning_root)
+    }
+}
+
+/// Checks the preconditions on the attestation slices given to `winning_root_for_attestations`.
+///
+/// Returns `BeaconStateError::AttestationInBothEpochs` if an attestation is in both slices and
+/// `BeaconStateError::AttestationOutOfEpoch` if an attestation is not in the epoch of its slice.
+///
+/// The overlap check is quadratic, so the `winning_root` functions only assert it in debug builds.
+/// All builds check the epochs.
+pub fn validate_inputs(
+    state: &BeaconState,
+    previous_epoch_attestations: &[PendingAttestation],
+    current_epoch_attestations: &[PendingAttestation],
+    spec: &ChainSpec,
+) -> Result<(), BeaconStateError> {
+    if let Some(a) = previous_epoch_attestations
+        .iter()
+        .find(|a| current_epoch_attestations.contains(a))
+    {
+        return Err(BeaconStateError::AttestationInBothEpochs { slot: a.data.slot });
+    }
+
+    verify_attestation_epochs(
+        previous_epoch_attestations,
+        state.previous_epoch(spec),
+        spec,
+    )?;
+    verify_attestation_epochs(current_epoch_attestations, state.current_epoch(spec), spec)
+}
+
+/// Returns an error if any of the `attestations` are not in the given `epoch`.
+fn verify_attestation_epochs(
+    attestations: &[PendingAttestation],
+    epoch: Epoch,
+    spec: &ChainSpec,
+) -> Result<(), BeaconStateError> {
+    match attestations
+        .iter()
+        .find(|a| a.data.slot.epoch(spec.slots_per_epoch) != epoch)
+    {
+        Some(a) => Err(BeaconStateError::AttestationOutOfEpoch {
+            slot: a.data.slot,
+            epoch,
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Returns `true` if pending attestation `a` is eligible to become a winning root.
+///
+/// Spec v0.5.0
+fn is_eligible_for_winning_root(state: &BeaconState, a: &PendingAttestation, shard: Shard) -> bool {
+    if shard >= state.latest_crosslinks.len() as u64 {
+        return false;
+    }
+
+    a.data.previous_crosslink == state.latest_crosslinks[shard as usize]
+}
+
+/// Returns the crosslink committee for `shard` in the previous epoch or, if there is none, the
+/// current epoch.
+///
+/// The previous epoch is preferred as it is the committee which is rewarded for the crosslink.
+fn shard_committee<'a>(
+    state: &'a BeaconState,
+    shard: Shard,
+    spec: &ChainSpec,
+) -> Result<&'a CrosslinkCommittee, BeaconStateError> {
+    match state.get_crosslink_committee_for_shard(state.previous_epoch(spec), shard, spec) {
+        Err(BeaconStateError::NoCommitteeForShardInEpoch { .. }) => {
+            state.get_crosslink_committee_for_shard(state.current_epoch(spec), shard, spec)
+        }
+        result => result,
+    }
+}
+
+/// Returns the attestation which "owns" each validator attesting to `crosslink_data_root` for
+/// `shard`, as a list of `(validator_index, attestation_index)` sorted by `validator_index`.
+///
+/// The `attestation_index` is the position of the attestation in
+/// `state.previous_epoch_attestations` chained with `state.current_epoch_attestations`. Where a
+/// validator is a participant of several attestations, the attestation with the earliest
+/// `inclusion_slot` owns it, ties are broken by the lowest `attestation_index`.
+///
+/// The validators are identical to the `attesting_validator_indices` of the corresponding
+/// `WinningRoot`.
+pub fn attesting_validator_inclusions(
+    state: &BeaconState,
+    shard: Shard,
+    crosslink_data_root: &Hash256,
+    spec: &ChainSpec,
+) -> Result<Vec<(usize, usize)>, BeaconStateError> {
+    // Maps each validator to the `(inclusion_slot, attestation_index)` of its owner.
+    let mut owners: BTreeMap<usize, (Slot, usize)> = BTreeMap::new();
+
+    let attestations = state
+        .previous_epoch_attestations_safe(spec)
+        .iter()
+        .chain(state.current_epoch_attestations.iter())
+        .enumerate();
+
+    for (attestation_index, a) in attestations {
+        if (a.data.shard != shard)
+            || (a.data.crosslink_data_root != *crosslink_data_root)
+            || !has_participants(a)
+            || !is_eligible_for_winning_root(state, a, shard)
+        {
+            continue;
+        }
+
+        let owner = (a.inclusion_slot, attestation_index);
+        for validator_index in
+            get_attestation_participants(state, &a.data, &a.aggregation_bitfield, spec)?
+        {
+            let current = owners.entry(validator_index).or_insert(owner);
+            if owner < *current {
+                *current = owner;
+            }
+        }
+    }
+
+    Ok(owners
+        .into_iter()
+        .map(|(validator_index, (_, attestation_index))| (validator_index, attestation_index))
+        .collect())
+}
+
+/// Returns `false` if the `aggregation_bitfield` of `a` marks no participants.
+fn has_participants(a: &PendingAttestation) -> bool {
+    !a.aggregation_bitfield.is_zero()
+}
+
+/// Returns the `attestations` which are for `shard`, have participants and are eligible to become
+/// a winning root, in order.
+///
+/// Only these attestations are counted towards a candidate, as per the `valid_attestations` of
+/// the spec. Attestations without participants contribute no balance, they must not create
+/// candidates.
+///
+/// The attestations are filtered before any committee is looked up, so an attestation for another
+/// shard can neither cost a lookup nor cause an error.
+fn eligible_attestations<'a, I>(
+    state: &BeaconState,
+    shard: Shard,
+    attestations: I,
+) -> Vec<&'a PendingAttestation>
+where
+    I: Iterator<Item = &'a PendingAttestation>,
+{
+    attestations
+        .filter(|a| {
+            (a.data.shard == shard)
+                && has_participants(a)
+                && is_eligible_for_winning_root(state, a, shard)
+        })
+        .collect()
+}
+
+/// Returns all indices which participated in the `attestations`. Does not contain duplicates.
+///
+/// A validator which participates in more than one attestation (e.g., in different slots, or in
+/// both epochs) is listed once.
+///
+/// Spec v0.5.0
+fn get_attesting_validator_indices<R>(
+    resolver: &R,
+    attestations: &[&PendingAttestation],
+) -> Result<Vec<usize>, BeaconStateError>
+where
+    R: ParticipantResolver + ?Sized,
+{
+    let mut indices = vec![];
+
+    for a in attestations {
+        indices.append(&mut resolver.participants(&a.data, &a.aggregation_bitfield)?);
+    }
+
+    // Sort the list (required for dedup). "Unstable" means the sort may re-order equal elements,
+    // this causes no issue here.
+    //
+    // These sort + dedup ops are potentially good CPU time optimisation targets.
+    indices.sort_unstable();
+    // Remove all duplicate indices (requires a sorted list).
+    indices.dedup();
+
+    Ok(indices)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{fs::File, path::PathBuf};
+    use types::test_utils::TestingBeaconStateBuilder;
+
+    /// Returns a state where each committee of the previous and current epochs has fully
+    /// participated in an attestation which is eligible to become a winning root.
+    fn get_state(spec: &ChainSpec) -> BeaconState {
+        get_state_with_validators(8, spec)
+    }
+
+    /// Identical to `get_state`, with `validator_count` validators.
+    fn get_state_with_validators(validator_count: usize, spec: &ChainSpec) -> BeaconState {
+        let mut builder =
+            TestingBeaconStateBuilder::from_deterministic_keypairs(validator_count, spec);
+        let slot = (spec.genesis_epoch + 4).end_slot(spec.slots_per_epoch);
+        builder.teleport_to_slot(slot, spec);
+        builder.insert_attestations(spec);
+        let (mut state, _keypairs) = builder.build();
+
+        let latest_crosslinks = state.latest_crosslinks.clone();
+        for a in state
+            .previous_epoch_attestations
+            .iter_mut()
+            .chain(state.current_epoch_attestations.iter_mut())
+        {
+            a.data.previous_crosslink = latest_crosslinks[a.data.shard as usize].clone();
+        }
+
+        state
+    }
+
+    /// Returns a `crosslink_data_root` of `[byte; 32]`.
+    fn root(byte: u8) -> Hash256 {
+        Hash256::from_slice(&[byte; 32])
+    }
+
+    /// Replaces the attestations of the shard of `template` with one for each `(root, positions)`,
+    /// a copy of `template` for `root` attested to by the members of the committee at `positions`.
+    ///
+    /// The new attestations are appended to `state.previous_epoch_attestations`, in order.
+    fn set_shard_attestations(
+        state: &mut BeaconState,
+        template: &PendingAttestation,
+        attestations: &[(Hash256, Vec<usize>)],
+    ) {
+        let shard = template.data.shard;
+        state
+            .previous_epoch_attestations
+            .retain(|a| a.data.shard != shard);
+        state
+            .current_epoch_attestations
+            .retain(|a| a.data.shard != shard);
+
+        for (root, positions) in attestations {
+            let mut a = template.clone();
+            a.data.crosslink_data_root = *root;
+            a.aggregation_bitfield =
+                Bitfield::from_elem(template.aggregation_bitfield.len(), false);
+            for &position in positions {
+                a.aggregation_bitfield.set(position, true);
+            }
+            state.previous_epoch_attestations.push(a);
+        }
+    }
+
+    /// Returns a state from `get_state_with_validators` and the shard of its first previous epoch
+    /// attestation, which only has the given `attestations` (see `set_shard_attestations`).
+    fn state_with_attestations(
+        validator_count: usize,
+        attestations: &[(Hash256, Vec<usize>)],
+        spec: &ChainSpec,
+    ) -> (BeaconState, Shard) {
+        let mut state = get_state_with_validators(validator_count, spec);
+        let template = state.previous_epoch_attestations[0].clone();
+        set_shard_attestations(&mut state, &template, attestations);
+
+        (state, template.data.shard)
+    }
+
+    #[test]
+    fn is_better_than() {
+        let worse = WinningRoot {
+            crosslink_data_root: Hash256::from_slice(&[1; 32]),
+            attesting_validator_indices: vec![],
+            total_attesting_balance: 42,
+            committee_size: 1,
+            committee_total_balance: 42,
+            num_contributing_attestations: 1,
+        };
+
+        let better = WinningRoot {
+            crosslink_data_root: Hash256::from_slice(&[2; 32]),
+            ..worse.clone()
+        };
 
         assert!(better.is_better_than(&worse));
+
+        let better = WinningRoot {
+            total_attesting_balance: worse.total_attesting_balance + 1,
+            ..worse.clone()
+        };
+
+        assert!(better.is_better_than(&worse));
+    }
+
+    #[test]
+    fn compare_roots_is_big_endian() {
+        // A little-endian comparison would find `low < high` reversed.
+        let mut low = [0; 32];
+        low[31] = 0xff;
+        let mut high = [0; 32];
+        high[0] = 0x01;
+        let (low, high) = (Hash256::from_slice(&low), Hash256::from_slice(&high));
+
+        assert_eq!(compare_roots(&low, &high), Ordering::Less);
+        assert_eq!(compare_roots(&high, &low), Ordering::Greater);
+        assert_eq!(compare_roots(&low, &low), Ordering::Equal);
+        assert_eq!(compare_roots(&low, &high), low.cmp(&high));
+
+        let candidate = |crosslink_data_root| WinningRoot {
+            crosslink_data_root,
+            attesting_validator_indices: vec![],
+            total_attesting_balance: 42,
+            committee_size: 1,
+            committee_total_balance: 42,
+            num_contributing_attestations: 1,
+        };
+
+        // On a tie, the higher big-endian root wins.
+        assert!(candidate(high).is_better_than(&candidate(low)));
+        assert!(!candidate(low).is_better_than(&candidate(high)));
+        assert!(candidate(low).is_better_than_with(&candidate(high), TieBreak::LowerRoot));
+        assert!(candidate(high) > candidate(low));
+    }
+
+    #[test]
+    fn max_matches_is_better_than() {
+        let candidate = |byte: u8, total_attesting_balance: u64| WinningRoot {
+            crosslink_data_root: root(byte),
+            attesting_validator_indices: vec![byte as usize],
+            total_attesting_balance,
+            committee_size: 1,
+            committee_total_balance: 42,
+            num_contributing_attestations: 1,
+        };
+
+        let candidates = vec![
+            candidate(3, 10),
+            candidate(1, 42),
+            candidate(7, 5),
+            candidate(2, 42),
+            candidate(0, 42),
+        ];
+
+        let mut winner: Option<&WinningRoot> = None;
+        for candidate in &candidates {
+            match winner {
+                Some(w) if !candidate.is_better_than(w) => {}
+                _ => winner = Some(candidate),
+            }
+        }
+
+        assert_eq!(candidates.iter().max(), winner);
+        assert_eq!(candidates.iter().max(), Some(&candidate(2, 42)));
+
+        for a in &candidates {
+            for b in &candidates {
+                assert_eq!(a > b, a.is_better_than(b));
+            }
+        }
+    }
+
+    #[test]
+    fn winning_root_tie_break() {
+        let spec = ChainSpec::few_validators();
+
+        // Two roots with identical participants, and therefore identical balances.
+        let (state, shard) =
+            state_with_attestations(64, &[(root(1), vec![0, 1]), (root(2), vec![2, 3])], &spec);
+
+        let with_tie_break = |tie_break| {
+            let options = WinningRootOptions {
+                tie_break,
+                ..WinningRootOptions::default()
+            };
+            winning_root_with_options(&state, shard, &options, &spec)
+                .unwrap()
+                .unwrap()
+        };
+        let lower = with_tie_break(TieBreak::LowerRoot);
+        let higher = with_tie_break(TieBreak::HigherRoot);
+
+        assert_eq!(
+            lower.total_attesting_balance,
+            higher.total_attesting_balance
+        );
+        assert_eq!(lower.crosslink_data_root, root(1));
+        assert_eq!(higher.crosslink_data_root, root(2));
+
+        assert_eq!(TieBreak::default(), TieBreak::HigherRoot);
+        assert_eq!(winning_root(&state, shard, &spec), Ok(Some(higher.clone())));
+
+        assert!(lower.is_better_than_with(&higher, TieBreak::LowerRoot));
+        assert!(higher.is_better_than_with(&lower, TieBreak::HigherRoot));
+    }
+
+    #[test]
+    fn winning_root_is_independent_of_order() {
+        let spec = ChainSpec::few_validators();
+        let mut state = get_state(&spec);
+
+        let shard = state.previous_epoch_attestations[0].data.shard;
+
+        // Several roots with identical balances, so the winner depends only on the tie-break.
+        state
+            .current_epoch_attestations
+            .retain(|a| a.data.shard != shard);
+        let original = state.previous_epoch_attestations[0].clone();
+        for byte in 1..6 {
+            let mut a = original.clone();
+            a.data.crosslink_data_root = Hash256::from_slice(&[byte; 32]);
+            state.previous_epoch_attestations.push(a);
+        }
+
+        let expected = winning_root(&state, shard, &spec).unwrap().unwrap();
+        assert_eq!(
+            expected.crosslink_data_root,
+            std::cmp::max(
+                original.data.crosslink_data_root,
+                Hash256::from_slice(&[5; 32])
+            )
+        );
+
+        // The attestations are rotated so the roots are found in a different order each time.
+        for _ in 0..state.previous_epoch_attestations.len() {
+            state.previous_epoch_attestations.rotate_left(1);
+            assert_eq!(
+                winning_root(&state, shard, &spec).unwrap(),
+                Some(expected.clone())
+            );
+        }
+    }
+
+    #[test]
+    fn candidates_are_deterministic() {
+        let spec = ChainSpec::few_validators();
+        let mut state = get_state(&spec);
+
+        let original = state.previous_epoch_attestations[0].clone();
+        let shard = original.data.shard;
+
+        for byte in &[5, 1, 4, 2, 3] {
+            let mut a = original.clone();
+            a.data.crosslink_data_root = Hash256::from_slice(&[*byte; 32]);
+            state.previous_epoch_attestations.push(a);
+        }
+
+        let candidate_roots = |state: &BeaconState| -> Vec<Hash256> {
+            winning_root_candidates(
+                state,
+                shard,
+                state.previous_epoch_attestations_safe(&spec),
+                &state.current_epoch_attestations,
+                &(state, &spec),
+                &WinningRootOptions::default(),
+                &spec,
+            )
+            .unwrap()
+            .iter()
+            .map(|c| c.crosslink_data_root)
+            .collect()
+        };
+
+        let expected_roots = candidate_roots(&state);
+        let mut sorted_roots = expected_roots.clone();
+        sorted_roots.sort();
+        assert_eq!(expected_roots, sorted_roots);
+
+        let expected = winning_root_verbose(&state, shard, &spec).unwrap();
+        assert!(expected.is_some());
+
+        for _ in 0..state.previous_epoch_attestations.len() {
+            state.previous_epoch_attestations.rotate_left(1);
+            assert_eq!(candidate_roots(&state), expected_roots);
+            assert_eq!(
+                winning_root_verbose(&state, shard, &spec).unwrap(),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn debug_uses_hex_root() {
+        let root = Hash256::from_slice(&[0xab; 32]);
+        let hex = format!("0x{}", "ab".repeat(32));
+
+        assert_eq!(format!("{:#x}", root), hex);
+
+        let winning_root = WinningRoot {
+            crosslink_data_root: root,
+            attesting_validator_indices: vec![1, 2],
+            total_attesting_balance: 42,
+            committee_size: 2,
+            committee_total_balance: 42,
+            num_contributing_attestations: 1,
+        };
+
+        assert!(format!("{:?}", winning_root).contains(&hex));
+        assert_eq!(format!("{}", winning_root), hex);
+    }
+
+    #[test]
+    fn shard_without_attestations() {
+        let spec = ChainSpec::few_validators();
+        let (state, shard) = state_with_attestations(8, &[], &spec);
+        assert!(!state.previous_epoch_attestations.is_empty());
+
+        // Every remaining attestation is eligible for `shard`, but none attest to it.
+        assert_eq!(winning_root(&state, shard, &spec), Ok(None));
+
+        let other_shard = state.previous_epoch_attestations[0].data.shard;
+        assert!(winning_root(&state, other_shard, &spec).unwrap().is_some());
+    }
+
+    #[test]
+    fn empty_bitfield_is_not_a_candidate() {
+        let spec = ChainSpec::few_validators();
+        let (state, shard) = state_with_attestations(8, &[(root(1), vec![])], &spec);
+
+        assert_eq!(winning_root(&state, shard, &spec), Ok(None));
+        assert_eq!(winning_root_verbose(&state, shard, &spec), Ok(None));
+    }
+
+    #[test]
+    fn attesting_validator_inclusions_prefers_earliest() {
+        let spec = ChainSpec::few_validators();
+        let mut state = get_state(&spec);
+
+        let shard = state.previous_epoch_attestations[0].data.shard;
+        state
+            .current_epoch_attestations
+            .retain(|a| a.data.shard != shard);
+
+        // The same attestation included twice, the later copy has the earlier inclusion slot.
+        let mut a = state.previous_epoch_attestations[0].clone();
+        a.inclusion_slot -= 1;
+        state.previous_epoch_attestations.push(a);
+        let earliest = state.previous_epoch_attestations.len() - 1;
+
+        let winning_root = winning_root(&state, shard, &spec).unwrap().unwrap();
+        let inclusions =
+            attesting_validator_inclusions(&state, shard, &winning_root.crosslink_data_root, &spec)
+                .unwrap();
+
+        let validators: Vec<usize> = inclusions.iter().map(|(i, _)| *i).collect();
+        assert_eq!(validators, winning_root.attesting_validator_indices);
+        assert!(inclusions.iter().all(|(_, a)| *a == earliest));
+
+        // With equal inclusion slots, the first attestation owns the validators.
+        state.previous_epoch_attestations[earliest].inclusion_slot += 1;
+        let inclusions =
+            attesting_validator_inclusions(&state, shard, &winning_root.crosslink_data_root, &spec)
+                .unwrap();
+        assert!(inclusions.iter().all(|(_, a)| *a == 0));
+    }
+
+    #[test]
+    fn num_contributing_attestations() {
+        let spec = ChainSpec::few_validators();
+
+        // Two attestations for the same root, one without participants and a single participant
+        // for a competing root.
+        let (state, shard) = state_with_attestations(
+            64,
+            &[
+                (root(0), (0..8).collect()),
+                (root(0), (0..8).collect()),
+                (root(0), vec![]),
+                (root(9), vec![0]),
+            ],
+            &spec,
+        );
+
+        let winning_root = winning_root(&state, shard, &spec).unwrap().unwrap();
+        assert_eq!(winning_root.crosslink_data_root, root(0));
+        assert_eq!(winning_root.num_contributing_attestations, 2);
+
+        let (_, candidates) = winning_root_verbose(&state, shard, &spec).unwrap().unwrap();
+        assert_eq!(candidates.len(), 2);
+    }
+
+    #[test]
+    fn ineligible_attestations_are_not_counted() {
+        let spec = ChainSpec::few_validators();
+
+        // Only the first member of the committee attests with an eligible attestation, the whole
+        // committee attests to the same root with a different `previous_crosslink`.
+        let (mut state, shard) = state_with_attestations(
+            64,
+            &[(root(0), vec![0]), (root(0), (0..8).collect())],
+            &spec,
+        );
+        let ineligible = state.previous_epoch_attestations.last_mut().unwrap();
+        ineligible.data.previous_crosslink.epoch += 1;
+
+        let winner = winning_root(&state, shard, &spec).unwrap().unwrap();
+        assert_eq!(winner.attesting_validator_indices.len(), 1);
+        assert_eq!(winner.num_contributing_attestations, 1);
+        assert_eq!(
+            attesting_validator_inclusions(&state, shard, &winner.crosslink_data_root, &spec)
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn same_validator_at_different_slots_is_counted_once() {
+        let spec = ChainSpec::few_validators();
+        let state = get_state_with_validators(64, &spec);
+
+        let participants = |a: &PendingAttestation| {
+            get_attestation_participants(&state, &a.data, &a.aggregation_bitfield, &spec).unwrap()
+        };
+
+        // Every attestation of the builder attests to the same (zero) root, so the attestations
+        // of both epochs for a shard are for the same root at different slots.
+        let mut overlapping_shards = 0;
+        for previous in &state.previous_epoch_attestations {
+            let shard = previous.data.shard;
+            let current = match state
+                .current_epoch_attestations
+                .iter()
+                .find(|a| a.data.shard == shard)
+            {
+                Some(current) => current,
+                None => continue,
+            };
+            assert_ne!(previous.data.slot, current.data.slot);
+            assert_eq!(
+                previous.data.crosslink_data_root,
+                current.data.crosslink_data_root
+            );
+
+            let previous_participants = participants(previous);
+            let current_participants = participants(current);
+            if previous_participants
+                .iter()
+                .any(|i| current_participants.contains(i))
+            {
+                overlapping_shards += 1;
+            }
+
+            let mut expected = previous_participants;
+            expected.extend_from_slice(&current_participants);
+            expected.sort_unstable();
+            expected.dedup();
+
+            let winning_root = winning_root(&state, shard, &spec).unwrap().unwrap();
+            assert_eq!(winning_root.attesting_validator_indices, expected);
+            assert_eq!(
+                winning_root.total_attesting_balance,
+                state.get_total_balance(&expected, &spec).unwrap()
+            );
+            assert_eq!(winning_root.num_contributing_attestations, 2);
+        }
+
+        // At least one validator must have attested in both slots for the test to be meaningful.
+        assert!(overlapping_shards > 0);
+    }
+
+    #[test]
+    fn winning_root_with_max_inclusion_slot_excludes_late_attestations() {
+        let spec = ChainSpec::few_validators();
+
+        // A single participant for `root(1)`, included early. The whole committee for `root(2)`,
+        // included later.
+        let (mut state, shard) = state_with_attestations(
+            64,
+            &[(root(1), vec![0]), (root(2), (0..8).collect())],
+            &spec,
+        );
+        let len = state.previous_epoch_attestations.len();
+        let small_inclusion_slot = state.previous_epoch_attestations[len - 2].inclusion_slot;
+        let large_inclusion_slot = small_inclusion_slot + 2;
+        state.previous_epoch_attestations[len - 1].inclusion_slot = large_inclusion_slot;
+
+        let winner = |max_inclusion_slot| {
+            let options = WinningRootOptions {
+                max_inclusion_slot,
+                ..WinningRootOptions::default()
+            };
+            winning_root_with_options(&state, shard, &options, &spec)
+                .unwrap()
+                .map(|w| w.crosslink_data_root)
+        };
+
+        assert_eq!(winner(None), Some(root(2)));
+        assert_eq!(winner(Some(large_inclusion_slot)), Some(root(2)));
+        assert_eq!(winner(Some(large_inclusion_slot - 1)), Some(root(1)));
+        assert_eq!(winner(Some(small_inclusion_slot - 1)), None);
+    }
+
+    #[test]
+    fn winning_root_cache() {
+        let spec = ChainSpec::few_validators();
+        let mut state = get_state(&spec);
+        let shard = state.previous_epoch_attestations[0].data.shard;
+
+        let mut cache = WinningRootCache::new();
+
+        let expected = winning_root(&state, shard, &spec).unwrap();
+        assert!(expected.is_some());
+
+        assert_eq!(
+            cache.winning_root(&state, shard, &spec),
+            Ok(expected.clone())
+        );
+        assert_eq!(cache.len(), 1);
+
+        // Replace the cached value, the next call should return it if it hits the cache.
+        cache.winning_roots.insert(shard, None);
+        assert_eq!(cache.winning_root(&state, shard, &spec), Ok(None));
+
+        // Modifying a balance invalidates the cache.
+        state.validator_balances_mut()[0] += 1;
+        assert_eq!(
+            cache.winning_root(&state, shard, &spec),
+            Ok(expected.clone())
+        );
+        assert_eq!(cache.len(), 1);
+
+        // Adding an attestation (even for another shard) invalidates the cache.
+        cache.winning_roots.insert(shard, None);
+        let a = state
+            .previous_epoch_attestations
+            .iter()
+            .find(|a| a.data.shard != shard)
+            .cloned()
+            .unwrap();
+        state.previous_epoch_attestations.push(a);
+        assert_eq!(
+            cache.winning_root(&state, shard, &spec),
+            Ok(expected.clone())
+        );
+        assert_eq!(cache.len(), 1);
+
+        // Modifying an attestation in place invalidates the cache.
+        cache.winning_roots.insert(shard, None);
+        let last = state.previous_epoch_attestations.len() - 1;
+        state.previous_epoch_attestations[last].inclusion_slot += 1;
+        assert_eq!(
+            cache.winning_root(&state, shard, &spec),
+            Ok(expected.clone())
+        );
+        assert_eq!(cache.len(), 1);
+
+        // Changing the latest crosslink makes the attestations for `shard` ineligible, the cache
+        // must not return the previous result.
+        state.latest_crosslinks[shard as usize].crosslink_data_root = root(42);
+        assert_eq!(winning_root(&state, shard, &spec), Ok(None));
+        assert_eq!(cache.winning_root(&state, shard, &spec), Ok(None));
+    }
+
+    #[test]
+    fn winning_root_for_each_target_epoch() {
+        let spec = ChainSpec::few_validators();
+        let state = get_state(&spec);
+
+        for &relative_epoch in &[RelativeEpoch::Previous, RelativeEpoch::Current] {
+            let attestations = state.get_epoch_attestations(relative_epoch).unwrap();
+            assert!(!attestations.is_empty());
+
+            let options = WinningRootOptions {
+                target_epoch: Some(relative_epoch.into_epoch(state.current_epoch(&spec))),
+                ..WinningRootOptions::default()
+            };
+
+            // There is only one attestation for each shard in an epoch.
+            for a in attestations {
+                let mut expected =
+                    get_attestation_participants(&state, &a.data, &a.aggregation_bitfield, &spec)
+                        .unwrap();
+                expected.sort_unstable();
+
+                let winning_root = winning_root_with_options(&state, a.data.shard, &options, &spec)
+                    .unwrap()
+                    .unwrap();
+
+                assert_eq!(winning_root.attesting_validator_indices, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn serde_round_trip() {
+        let winning_root = WinningRoot {
+            crosslink_data_root: Hash256::from_slice(&[0xab; 32]),
+            attesting_validator_indices: vec![1, 3, 5],
+            total_attesting_balance: 96_000_000_000,
+            committee_size: 4,
+            committee_total_balance: 128_000_000_000,
+            num_contributing_attestations: 1,
+        };
+
+        let json = serde_json::to_string(&winning_root).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "crosslink_data_root": format!("0x{}", "ab".repeat(32)),
+                "attesting_validator_indices": [1, 3, 5],
+                "total_attesting_balance": 96_000_000_000_u64,
+                "committee_size": 4,
+                "committee_total_balance": 128_000_000_000_u64,
+                "num_contributing_attestations": 1,
+            })
+        );
+
+        let decoded: WinningRoot = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, winning_root);
+    }
+
+    #[test]
+    fn winning_root_verbose_lists_all_candidates() {
+        let spec = ChainSpec::few_validators();
+        let mut state = get_state(&spec);
+
+        let shard = state.previous_epoch_attestations[0].data.shard;
+
+        // Give the shard three competing roots, the previous epoch attestation is copied so that
+        // at least two of the roots have identical balances.
+        let mut a = state.previous_epoch_attestations[0].clone();
+        state.previous_epoch_attestations[0]
+            .data
+            .crosslink_data_root = root(1);
+        a.data.crosslink_data_root = root(2);
+        state.previous_epoch_attestations.push(a);
+        for a in state
+            .current_epoch_attestations
+            .iter_mut()
+            .filter(|a| a.data.shard == shard)
+        {
+            a.data.crosslink_data_root = root(3);
+        }
+
+        let (winner, candidates) = winning_root_verbose(&state, shard, &spec).unwrap().unwrap();
+
+        let mut roots: Vec<Hash256> = candidates.iter().map(|(root, _)| *root).collect();
+        roots.sort();
+        assert_eq!(roots, vec![root(1), root(2), root(3)]);
+
+        // Sorted from best to worst.
+        for pair in candidates.windows(2) {
+            let (better_root, better_balance) = pair[0];
+            let (worse_root, worse_balance) = pair[1];
+            assert!(
+                (better_balance > worse_balance)
+                    || ((better_balance == worse_balance) && (better_root > worse_root))
+            );
+        }
+
+        assert_eq!(
+            candidates[0],
+            (winner.crosslink_data_root, winner.total_attesting_balance)
+        );
+        assert_eq!(Some(winner), winning_root(&state, shard, &spec).unwrap());
+
+        assert_eq!(
+            winning_root_verbose(&state, spec.shard_count, &spec),
+            Ok(None)
+        );
+    }
+
+    #[test]
+    fn winning_root_with_runner_up_of_three_candidates() {
+        let spec = ChainSpec::few_validators();
+
+        // Each root is attested to by the committee members at the given positions.
+        let (mut state, shard) = state_with_attestations(
+            64,
+            &[
+                (root(3), vec![0, 1, 2, 3]),
+                (root(2), vec![4]),
+                (root(1), vec![5, 6]),
+            ],
+            &spec,
+        );
+
+        let balance = spec.max_deposit_amount;
+        let (winner, runner_up) = winning_root_with_runner_up(&state, shard, &spec)
+            .unwrap()
+            .unwrap();
+        assert_eq!(winner.crosslink_data_root, root(3));
+        assert_eq!(runner_up, Some((root(1), 2 * balance)));
+
+        // With equal balances, the runner-up is the higher root (as for the winner).
+        let len = state.previous_epoch_attestations.len();
+        state.previous_epoch_attestations[len - 2]
+            .aggregation_bitfield
+            .set(7, true);
+        let (_, runner_up) = winning_root_with_runner_up(&state, shard, &spec)
+            .unwrap()
+            .unwrap();
+        assert_eq!(runner_up, Some((root(2), 2 * balance)));
+
+        // A single candidate has no runner-up.
+        state.previous_epoch_attestations.truncate(len - 2);
+        let (winner, runner_up) = winning_root_with_runner_up(&state, shard, &spec)
+            .unwrap()
+            .unwrap();
+        assert_eq!(winner.crosslink_data_root, root(3));
+        assert_eq!(runner_up, None);
+    }
+
+    /// Resolves participants from a single committee, with fixed balances.
+    struct MockResolver {
+        committee: Vec<usize>,
+        balances: Vec<u64>,
+    }
+
+    impl ParticipantResolver for MockResolver {
+        fn participants(
+            &self,
+            _attestation_data: &AttestationData,
+            bitfield: &Bitfield,
+        ) -> Result<Vec<usize>, BeaconStateError> {
+            Ok(self
+                .committee
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| bitfield.get(*i).unwrap_or(false))
+                .map(|(_, validator_index)| *validator_index)
+                .collect())
+        }
+
+        fn effective_balance(&self, validator_index: usize) -> Result<u64, BeaconStateError> {
+            Ok(self.balances[validator_index])
+        }
+    }
+
+    #[test]
+    fn winning_root_with_mock_resolver() {
+        let mut resolver = MockResolver {
+            committee: vec![3, 1, 2, 0],
+            balances: vec![10; 4],
+        };
+
+        let attestation = |root: u8, bits: &[usize]| {
+            let mut a = PendingAttestation {
+                aggregation_bitfield: Bitfield::from_elem(4, false),
+                data: AttestationData {
+                    slot: Slot::new(0),
+                    beacon_block_root: Hash256::zero(),
+                    source_epoch: Epoch::new(0),
+                    source_root: Hash256::zero(),
+                    target_root: Hash256::zero(),
+                    shard: 5,
+                    previous_crosslink: Crosslink::default(),
+                    crosslink_data_root: Hash256::from_slice(&[root; 32]),
+                },
+                custody_bitfield: Bitfield::new(),
+                inclusion_slot: Slot::new(1),
+            };
+            for &bit in bits {
+                a.aggregation_bitfield.set(bit, true);
+            }
+            a
+        };
+        // Equal balances, the higher root wins. An attestation for another shard is ignored.
+        let mut attestations = vec![
+            attestation(1, &[0, 1]),
+            attestation(2, &[2]),
+            attestation(2, &[2, 3]),
+        ];
+        let mut other_shard = attestation(3, &[0, 1, 2, 3]);
+        other_shard.data.shard = 6;
+        attestations.push(other_shard);
+
+        let committee = resolver.committee.clone();
+        let winner = winning_root_with_resolver(&resolver, 5, &attestations, &committee)
+            .unwrap()
+            .unwrap();
+        assert_eq!(winner.crosslink_data_root, Hash256::from_slice(&[2; 32]));
+        assert_eq!(winner.attesting_validator_indices, vec![0, 2]);
+        assert_eq!(winner.total_attesting_balance, 20);
+        assert_eq!(winner.committee_total_balance, 40);
+        assert_eq!(winner.num_contributing_attestations, 2);
+
+        // With a higher balance, the lower root wins.
+        resolver.balances[3] = 11;
+        let winner = winning_root_with_resolver(&resolver, 5, &attestations, &committee)
+            .unwrap()
+            .unwrap();
+        assert_eq!(winner.crosslink_data_root, Hash256::from_slice(&[1; 32]));
+
+        assert_eq!(
+            winning_root_with_resolver(&resolver, 7, &attestations, &committee),
+            Ok(None)
+        );
+    }
+
+    #[test]
+    fn winning_root_with_state_resolver() {
+        let spec = ChainSpec::few_validators();
+        let state = get_state_with_validators(64, &spec);
+
+        let attestations: Vec<PendingAttestation> = state
+            .previous_epoch_attestations
+            .iter()
+            .chain(state.current_epoch_attestations.iter())
+            .cloned()
+            .collect();
+
+        for a in &state.previous_epoch_attestations {
+            let shard = a.data.shard;
+            let committee = &state
+                .get_crosslink_committee_for_shard(state.previous_epoch(&spec), shard, &spec)
+                .unwrap()
+                .committee;
+
+            assert_eq!(
+                winning_root_with_resolver(&(&state, &spec), shard, &attestations, committee),
+                winning_root(&state, shard, &spec)
+            );
+        }
+    }
+
+    #[test]
+    fn inclusion_weight_penalty_flips_winner() {
+        let spec = ChainSpec::few_validators();
+
+        // Four late attesters for one root, two prompt attesters for another.
+        let (late, prompt) = (root(1), root(2));
+        let (mut state, shard) =
+            state_with_attestations(64, &[(late, vec![0, 1, 2, 3]), (prompt, vec![4, 5])], &spec);
+        let len = state.previous_epoch_attestations.len();
+        for (a, inclusion_distance) in state.previous_epoch_attestations[len - 2..]
+            .iter_mut()
+            .zip(&[4_u64, 1])
+        {
+            a.inclusion_slot = a.data.slot + *inclusion_distance;
+        }
+
+        let with_inclusion_weight = |inclusion_weight: &dyn Fn(u64) -> f64| {
+            let options = WinningRootOptions {
+                inclusion_weight: Some(inclusion_weight),
+                ..WinningRootOptions::default()
+            };
+            winning_root_with_options(&state, shard, &options, &spec)
+                .unwrap()
+                .unwrap()
+        };
+
+        let uniform = with_inclusion_weight(&|_| 1.0);
+        assert_eq!(uniform.crosslink_data_root, late);
+        assert_eq!(Some(uniform), winning_root(&state, shard, &spec).unwrap());
+
+        let penalised = with_inclusion_weight(&|distance| if distance > 1 { 0.25 } else { 1.0 });
+        assert_eq!(penalised.crosslink_data_root, prompt);
+        assert_eq!(
+            penalised.total_attesting_balance,
+            2 * spec.max_deposit_amount
+        );
+
+        // Weights outside `[0.0, 1.0]` are clamped.
+        let clamped = with_inclusion_weight(&|distance| if distance > 1 { 2.0 } else { -1.0 });
+        assert_eq!(clamped.crosslink_data_root, late);
+        assert_eq!(clamped.total_attesting_balance, 4 * spec.max_deposit_amount);
+    }
+
+    #[test]
+    fn winning_root_tally_of_each_root() {
+        let spec = ChainSpec::few_validators();
+
+        // Two overlapping attestations for one root, and a single attester for another.
+        let (mut state, shard) = state_with_attestations(
+            64,
+            &[
+                (root(1), vec![0, 1, 2]),
+                (root(1), vec![2, 3]),
+                (root(2), vec![4]),
+            ],
+            &spec,
+        );
+
+        // A validator with a reduced balance.
+        let committee = state
+            .get_crosslink_committee_for_shard(state.previous_epoch(&spec), shard, &spec)
+            .unwrap()
+            .committee
+            .clone();
+        state.validator_balances_mut()[committee[4]] = spec.max_deposit_amount / 2;
+
+        let tally = winning_root_tally(&state, shard, &spec).unwrap();
+        let mut expected = HashMap::new();
+        expected.insert(root(1), 4 * spec.max_deposit_amount);
+        expected.insert(root(2), spec.max_deposit_amount / 2);
+        assert_eq!(tally, expected);
+
+        let json = serde_json::to_string(&tally).unwrap();
+        assert!(json.contains(&"02".repeat(32)));
+
+        let (state, shard) = state_with_attestations(64, &[], &spec);
+        assert!(winning_root_tally(&state, shard, &spec).unwrap().is_empty());
+    }
+
+    #[test]
+    fn winning_root_at_genesis() {
+        let spec = ChainSpec::few_validators();
+        let builder = TestingBeaconStateBuilder::from_deterministic_keypairs(8, &spec);
+        let (mut state, _keypairs) = builder.build();
+        assert_eq!(state.current_epoch(&spec), spec.genesis_epoch);
+
+        // Attestations in the previous list are ignored at genesis.
+        let mut later_state = get_state(&spec);
+        let a = later_state.previous_epoch_attestations.pop().unwrap();
+        let shard = a.data.shard;
+        state.previous_epoch_attestations.push(a);
+
+        assert!(state.previous_epoch_attestations_safe(&spec).is_empty());
+        assert_eq!(winning_root(&state, shard, &spec), Ok(None));
+    }
+
+    #[test]
+    fn committee_totals() {
+        let spec = ChainSpec::few_validators();
+        let state = get_state(&spec);
+
+        for a in &state.previous_epoch_attestations {
+            let shard = a.data.shard;
+            let winning_root = winning_root(&state, shard, &spec).unwrap().unwrap();
+
+            let committee = state
+                .get_crosslink_committee_for_shard(state.previous_epoch(&spec), shard, &spec)
+                .unwrap();
+            let expected: u64 = committee
+                .committee
+                .iter()
+                .map(|&i| state.get_effective_balance(i, &spec).unwrap())
+                .sum();
+
+            assert_eq!(winning_root.committee_size, committee.committee.len());
+            assert_eq!(winning_root.committee_total_balance, expected);
+        }
+    }
+
+    #[test]
+    fn adjust_balance() {
+        let mut winning_root = WinningRoot {
+            crosslink_data_root: Hash256::from_slice(&[1; 32]),
+            attesting_validator_indices: vec![1, 4, 7],
+            total_attesting_balance: 30,
+            committee_size: 4,
+            committee_total_balance: 40,
+            num_contributing_attestations: 1,
+        };
+
+        winning_root.adjust_balance(4, 10, 6);
+        assert_eq!(winning_root.total_attesting_balance, 26);
+
+        winning_root.adjust_balance(7, 10, 12);
+        assert_eq!(winning_root.total_attesting_balance, 28);
+
+        // A validator which did not attest.
+        let unchanged = winning_root.clone();
+        winning_root.adjust_balance(3, 10, 0);
+        assert_eq!(winning_root, unchanged);
+    }
+
+    #[test]
+    fn winning_root_with_uniform_weights() {
+        let spec = ChainSpec::few_validators();
+
+        // One validator with a full balance attests to `root(1)`, two validators with a quarter
+        // balance each attest to `root(2)`.
+        let (mut state, shard) =
+            state_with_attestations(64, &[(root(1), vec![0]), (root(2), vec![1, 2])], &spec);
+        let committee = state
+            .get_crosslink_committee_for_shard(state.previous_epoch(&spec), shard, &spec)
+            .unwrap()
+            .committee
+            .clone();
+        state.validator_balances_mut()[committee[1]] = spec.max_deposit_amount / 4;
+        state.validator_balances_mut()[committee[2]] = spec.max_deposit_amount / 4;
+
+        let with_weights = |weight_fn: &dyn Fn(usize) -> u64| {
+            let options = WinningRootOptions {
+                weight_fn: Some(weight_fn),
+                ..WinningRootOptions::default()
+            };
+            winning_root_with_options(&state, shard, &options, &spec).unwrap()
+        };
+
+        let by_balance = winning_root(&state, shard, &spec).unwrap().unwrap();
+        assert_eq!(by_balance.crosslink_data_root, root(1));
+        assert_eq!(by_balance.total_attesting_balance, spec.max_deposit_amount);
+
+        let by_count = with_weights(&|_| 1).unwrap();
+        assert_eq!(by_count.crosslink_data_root, root(2));
+        assert_eq!(by_count.total_attesting_balance, 2);
+
+        // Weighting by effective balance is identical to `winning_root`.
+        let by_weight = with_weights(&|i| state.get_effective_balance(i, &spec).unwrap());
+        assert_eq!(by_weight, Some(by_balance));
+    }
+
+    #[test]
+    fn validate() {
+        let valid = WinningRoot {
+            crosslink_data_root: Hash256::from_slice(&[1; 32]),
+            attesting_validator_indices: vec![0],
+            total_attesting_balance: 42,
+            committee_size: 1,
+            committee_total_balance: 42,
+            num_contributing_attestations: 1,
+        };
+        assert_eq!(valid.validate(), Ok(()));
+
+        let invalid = WinningRoot {
+            crosslink_data_root: Hash256::zero(),
+            ..valid.clone()
+        };
+        assert_eq!(
+            invalid.validate(),
+            Err(BeaconStateError::InvalidWinningRoot)
+        );
+
+        let empty = WinningRoot {
+            total_attesting_balance: 0,
+            ..invalid.clone()
+        };
+        assert_eq!(empty.validate(), Ok(()));
+    }
+
+    #[test]
+    fn validate_indices() {
+        let spec = ChainSpec::few_validators();
+        let state = get_state_with_validators(64, &spec);
+        let shard = state.previous_epoch_attestations[0].data.shard;
+
+        let valid = winning_root(&state, shard, &spec).unwrap().unwrap();
+        assert_eq!(valid.validate_indices(&state, shard, &spec), Ok(()));
+        assert_eq!(
+            winning_root_checked(&state, shard, &spec),
+            Ok(Some(valid.clone()))
+        );
+
+        let mut duplicate = valid.clone();
+        let i = duplicate.attesting_validator_indices[0];
+        duplicate.attesting_validator_indices.insert(0, i);
+        assert_eq!(
+            duplicate.validate_indices(&state, shard, &spec),
+            Err(BeaconStateError::DuplicateAttestingIndex(i))
+        );
+
+        // A validator which is not in any committee for the shard.
+        let bitmap = ShardCommitteeBitmap::new(&state, &spec).unwrap();
+        let outsider = (0..state.validator_registry.len())
+            .find(|&i| !bitmap.can_attest(shard, i))
+            .unwrap();
+        let not_in_committee = WinningRoot {
+            attesting_validator_indices: vec![outsider],
+            ..valid.clone()
+        };
+        assert_eq!(
+            not_in_committee.validate_indices(&state, shard, &spec),
+            Err(BeaconStateError::AttesterNotInCommittee {
+                validator_index: outsider,
+                shard,
+            })
+        );
+    }
+
+    #[test]
+    fn winning_root_with_bitmap_matches_participants() {
+        let spec = ChainSpec::few_validators();
+        let mut state = get_state(&spec);
+
+        // Give one shard competing roots, so there is more than one candidate.
+        let mut a = state.previous_epoch_attestations[0].clone();
+        a.data.crosslink_data_root = Hash256::from_slice(&[1; 32]);
+        state.previous_epoch_attestations.push(a);
+
+        let bitmap = ShardCommitteeBitmap::new(&state, &spec).unwrap();
+
+        for shard in 0..spec.shard_count {
+            let expected = winning_root(&state, shard, &spec).unwrap();
+
+            if let Some(winning_root) = &expected {
+                let mut participants = vec![];
+                for a in state
+                    .previous_epoch_attestations
+                    .iter()
+                    .chain(state.current_epoch_attestations.iter())
+                    .filter(|a| {
+                        (a.data.shard == shard)
+                            && (a.data.crosslink_data_root == winning_root.crosslink_data_root)
+                    })
+                {
+                    participants.append(
+                        &mut get_attestation_participants(
+                            &state,
+                            &a.data,
+                            &a.aggregation_bitfield,
+                            &spec,
+                        )
+                        .unwrap(),
+                    );
+                }
+                participants.sort_unstable();
+                participants.dedup();
+
+                assert_eq!(winning_root.attesting_validator_indices, participants);
+            }
+
+            let options = WinningRootOptions {
+                bitmap: Some(&bitmap),
+                ..WinningRootOptions::default()
+            };
+            assert_eq!(
+                winning_root_with_options(&state, shard, &options, &spec),
+                Ok(expected)
+            );
+        }
+    }
+
+    #[test]
+    fn historical_winning_roots_from_snapshot() {
+        let spec = ChainSpec::few_validators();
+        let state = get_state(&spec);
+        let epoch = state.current_epoch(&spec);
+
+        let expected: HashMap<Shard, WinningRoot> = (0..spec.shard_count)
+            .filter_map(|shard| {
+                winning_root(&state, shard, &spec)
+                    .unwrap()
+                    .map(|winning_root| (shard, winning_root))
+            })
+            .collect();
+        assert!(!expected.is_empty());
+
+        // The attestations as they would be found in blocks, in no particular order and including
+        // an attestation from an earlier epoch.
+        let mut included_attestations: Vec<PendingAttestation> = state
+            .current_epoch_attestations
+            .iter()
+            .chain(state.previous_epoch_attestations.iter())
+            .cloned()
+            .collect();
+        let mut old = included_attestations[0].clone();
+        old.data.slot -= 2 * spec.slots_per_epoch;
+        included_attestations.push(old);
+
+        // The snapshot does not need its own pending attestations.
+        let mut snapshot = state.clone();
+        snapshot.previous_epoch_attestations = vec![];
+        snapshot.current_epoch_attestations = vec![];
+
+        assert_eq!(
+            historical_winning_roots(&snapshot, &included_attestations, epoch, &spec),
+            Ok(expected)
+        );
+
+        assert_eq!(
+            historical_winning_roots(&snapshot, &included_attestations, epoch - 1, &spec),
+            Err(BeaconStateError::EpochOutOfBounds)
+        );
+    }
+
+    #[test]
+    fn winning_root_uses_capped_balances() {
+        let spec = ChainSpec::few_validators();
+        let mut state = get_state(&spec);
+        let shard = state.previous_epoch_attestations[0].data.shard;
+
+        let expected = winning_root(&state, shard, &spec).unwrap().unwrap();
+        let attester_count = expected.attesting_validator_indices.len() as u64;
+        assert_eq!(
+            expected.total_attesting_balance,
+            attester_count * spec.max_deposit_amount
+        );
+
+        // A raw balance above the cap contributes only the capped amount.
+        let attester = expected.attesting_validator_indices[0];
+        state.validator_balances_mut()[attester] = 2 * spec.max_deposit_amount;
+        assert_eq!(
+            winning_root(&state, shard, &spec),
+            Ok(Some(expected.clone()))
+        );
+
+        // The cap is read from the spec.
+        let mut capped_spec = spec.clone();
+        capped_spec.max_deposit_amount = spec.max_deposit_amount / 2;
+
+        let capped = winning_root(&state, shard, &capped_spec).unwrap().unwrap();
+        assert_eq!(
+            capped.total_attesting_balance,
+            attester_count * capped_spec.max_deposit_amount
+        );
+        assert_eq!(
+            capped.committee_total_balance,
+            capped.committee_size as u64 * capped_spec.max_deposit_amount
+        );
+
+        // The effective balance cache applies the same cap, and is not read for another spec.
+        state.build_effective_balance_cache(&capped_spec);
+        assert_eq!(winning_root(&state, shard, &capped_spec), Ok(Some(capped)));
+        assert_eq!(winning_root(&state, shard, &spec), Ok(Some(expected)));
+    }
+
+    #[test]
+    fn effective_balance_cache_is_reused_until_balances_change() {
+        let spec = ChainSpec::few_validators();
+        let mut state = get_state(&spec);
+
+        let shard = state.previous_epoch_attestations[0].data.shard;
+        let expected = winning_root(&state, shard, &spec).unwrap().unwrap();
+        let attester = expected.attesting_validator_indices[0];
+
+        // Without a modification of the balances, each call reads the cache.
+        state.build_effective_balance_cache(&spec);
+        for _ in 0..2 {
+            assert_eq!(
+                winning_root(&state, shard, &spec),
+                Ok(Some(expected.clone()))
+            );
+            assert!(state.effective_balance_cache_is_current(&spec));
+        }
+
+        // After a modification the cache is stale and the balances are read instead.
+        state.validator_balances_mut()[attester] = spec.max_deposit_amount / 2;
+        assert!(!state.effective_balance_cache_is_current(&spec));
+        let recomputed = winning_root(&state, shard, &spec).unwrap().unwrap();
+        assert_eq!(
+            recomputed.total_attesting_balance,
+            expected.total_attesting_balance - spec.max_deposit_amount / 2
+        );
+    }
+
+    #[test]
+    fn winning_root_in_arena_returns_index() {
+        let spec = ChainSpec::few_validators();
+        let state = get_state(&spec);
+
+        let mut shards: Vec<Shard> = state
+            .previous_epoch_attestations
+            .iter()
+            .map(|a| a.data.shard)
+            .collect();
+        shards.sort_unstable();
+        shards.dedup();
+        assert!(shards.len() > 1);
+
+        let mut arena = vec![];
+        for &shard in &shards {
+            let index = winning_root_in_arena(&state, shard, &mut arena, &spec)
+                .unwrap()
+                .unwrap();
+            assert_eq!(index, arena.len() - 1);
+            assert_eq!(
+                Some(arena[index].clone()),
+                winning_root(&state, shard, &spec).unwrap()
+            );
+        }
+        assert_eq!(arena.len(), shards.len());
+
+        // No winner leaves the arena unchanged.
+        assert_eq!(
+            winning_root_in_arena(&state, spec.shard_count, &mut arena, &spec),
+            Ok(None)
+        );
+        assert_eq!(arena.len(), shards.len());
+    }
+
+    #[test]
+    fn winning_root_with_filter_excludes_attestations() {
+        let spec = ChainSpec::few_validators();
+
+        // Two attestations by the same committee members for different roots, the higher root wins
+        // the tie.
+        let (state, shard) =
+            state_with_attestations(64, &[(root(1), vec![0]), (root(2), vec![0])], &spec);
+
+        let with_filter = |filter: &dyn Fn(&PendingAttestation) -> bool| {
+            let options = WinningRootOptions {
+                filter: Some(filter),
+                ..WinningRootOptions::default()
+            };
+            winning_root_with_options(&state, shard, &options, &spec)
+        };
+
+        let winner = winning_root(&state, shard, &spec).unwrap().unwrap();
+        assert_eq!(winner.crosslink_data_root, root(2));
+        assert_eq!(with_filter(&|_| true), Ok(Some(winner)));
+
+        let filtered = with_filter(&|a| a.data.crosslink_data_root != root(2))
+            .unwrap()
+            .unwrap();
+        assert_eq!(filtered.crosslink_data_root, root(1));
+
+        assert_eq!(with_filter(&|_| false), Ok(None));
+    }
+
+    #[test]
+    fn all_winning_roots_with_shards() {
+        let spec = ChainSpec::few_validators();
+        let mut state = get_state(&spec);
+
+        let shard = state.previous_epoch_attestations[0].data.shard;
+        state
+            .previous_epoch_attestations
+            .retain(|a| a.data.shard == shard);
+        state
+            .current_epoch_attestations
+            .retain(|a| a.data.shard == shard);
+
+        // Every shard with a committee has an entry.
+        let without_shards = all_winning_roots(&state, None, &spec).unwrap();
+        assert!(without_shards.len() > 1);
+        assert!(without_shards[&shard].is_some());
+        for (other_shard, winning_root) in &without_shards {
+            assert_eq!(winning_root.is_some(), *other_shard == shard);
+        }
+
+        let shards = [
+            shard,
+            (shard + 1) % spec.shard_count,
+            (shard + 2) % spec.shard_count,
+        ];
+        let with_shards = all_winning_roots(&state, Some(&shards), &spec).unwrap();
+
+        assert_eq!(with_shards.len(), 3);
+        assert_eq!(with_shards[&shard], without_shards[&shard]);
+        assert_eq!(with_shards[&shards[1]], None);
+        assert_eq!(with_shards[&shards[2]], None);
+    }
+
+    #[test]
+    fn winning_root_with_target_epoch_excludes_other_epochs() {
+        let spec = ChainSpec::few_validators();
+        let mut state = get_state_with_validators(64, &spec);
+
+        let shard = state
+            .previous_epoch_attestations
+            .iter()
+            .map(|a| a.data.shard)
+            .find(|shard| {
+                state
+                    .current_epoch_attestations
+                    .iter()
+                    .any(|a| a.data.shard == *shard)
+            })
+            .expect("a shard is attested to in both epochs");
+
+        // A single previous epoch attester, outweighed by the current epoch attesters of another
+        // root.
+        let previous = state
+            .previous_epoch_attestations
+            .iter_mut()
+            .find(|a| a.data.shard == shard)
+            .unwrap();
+        let len = previous.aggregation_bitfield.len();
+        previous.aggregation_bitfield = Bitfield::from_elem(len, false);
+        previous.aggregation_bitfield.set(0, true);
+        let previous_root = previous.data.crosslink_data_root;
+
+        let current_root = root(7);
+        state
+            .current_epoch_attestations
+            .iter_mut()
+            .filter(|a| a.data.shard == shard)
+            .for_each(|a| a.data.crosslink_data_root = current_root);
+
+        let winner = |target_epoch| {
+            let options = WinningRootOptions {
+                target_epoch,
+                ..WinningRootOptions::default()
+            };
+            winning_root_with_options(&state, shard, &options, &spec)
+                .unwrap()
+                .map(|winning_root| winning_root.crosslink_data_root)
+        };
+
+        assert_eq!(winner(None), Some(current_root));
+        assert_eq!(winner(Some(state.current_epoch(&spec))), Some(current_root));
+        assert_eq!(
+            winner(Some(state.previous_epoch(&spec))),
+            Some(previous_root)
+        );
+        assert_eq!(winner(Some(state.current_epoch(&spec) + 1)), None);
+    }
+
+    #[test]
+    fn try_all_winning_roots_records_errors() {
+        let spec = ChainSpec::few_validators();
+        let mut state = get_state(&spec);
+
+        // An attestation with a bitfield too long for its committee.
+        let broken_shard = state.previous_epoch_attestations[0].data.shard;
+        let len = state.previous_epoch_attestations[0]
+            .aggregation_bitfield
+            .len();
+        state.previous_epoch_attestations[0].aggregation_bitfield =
+            Bitfield::from_elem(len + 16, true);
+
+        assert_eq!(
+            all_winning_roots(&state, None, &spec),
+            Err(BeaconStateError::InvalidBitfield)
+        );
+
+        let results = try_all_winning_roots(&state, None, &spec).unwrap();
+        assert!(results.len() > 1);
+        for (shard, result) in &results {
+            if *shard == broken_shard {
+                assert_eq!(*result, Err(BeaconStateError::InvalidBitfield));
+            } else {
+                assert_eq!(*result, winning_root(&state, *shard, &spec));
+                assert!(result.as_ref().unwrap().is_some());
+            }
+        }
+    }
+
+    #[test]
+    fn try_all_winning_roots_isolates_committee_errors() {
+        let spec = ChainSpec::few_validators();
+        let mut state = get_state(&spec);
+
+        // An attestation claiming a shard which has no committee at the attestation's slot.
+        let a = &mut state.previous_epoch_attestations[0];
+        let slot = a.data.slot;
+        let broken_shard = (a.data.shard + 1) % spec.shard_count;
+        a.data.shard = broken_shard;
+
+        let results = try_all_winning_roots(&state, None, &spec).unwrap();
+        assert!(results.len() > 1);
+        for (shard, result) in &results {
+            if *shard == broken_shard {
+                assert_eq!(
+                    *result,
+                    Err(BeaconStateError::NoCommitteeForShard {
+                        shard: broken_shard,
+                        slot,
+                    })
+                );
+            } else {
+                assert!(result.is_ok());
+                assert_eq!(*result, winning_root(&state, *shard, &spec));
+            }
+        }
+    }
+
+    #[test]
+    fn diff_winning_roots_pinpoints_shard() {
+        let spec = ChainSpec::few_validators();
+        let state = get_state_with_validators(64, &spec);
+
+        let ours = all_winning_roots(&state, None, &spec).unwrap();
+        assert!(diff_winning_roots(&ours, &ours).is_empty());
+
+        let mut shards: Vec<Shard> = ours.keys().cloned().collect();
+        shards.sort_unstable();
+        let (shard, missing_shard) = (shards[0], shards[1]);
+
+        // They have a different root for `shard`, with one fewer and one different attester.
+        let mut theirs = ours.clone();
+        let ours_winner = ours[&shard].clone().unwrap();
+        {
+            let winner = theirs.get_mut(&shard).unwrap().as_mut().unwrap();
+            winner.crosslink_data_root = Hash256::from_slice(&[9; 32]);
+            winner.attesting_validator_indices.remove(0);
+            winner.attesting_validator_indices.push(1_000);
+        }
+        let diffs = diff_winning_roots(&ours, &theirs);
+
+        assert_eq!(
+            diffs,
+            vec![CrosslinkDiff {
+                shard,
+                our_root: Some(ours_winner.crosslink_data_root),
+                their_root: Some(Hash256::from_slice(&[9; 32])),
+                our_balance: ours_winner.total_attesting_balance,
+                their_balance: ours_winner.total_attesting_balance,
+                attesters_only_ours: vec![ours_winner.attesting_validator_indices[0]],
+                attesters_only_theirs: vec![1_000],
+            }]
+        );
+
+        // A shard they omit is compared as having no winning root.
+        theirs.remove(&missing_shard);
+        let diffs = diff_winning_roots(&ours, &theirs);
+        assert_eq!(diffs.len(), 2);
+        assert_eq!(diffs[0].shard, shard);
+        assert_eq!(diffs[1].shard, missing_shard);
+        assert_eq!(diffs[1].their_root, None);
+        assert_eq!(diffs[1].their_balance, 0);
+        assert_eq!(
+            diffs[1].attesters_only_ours,
+            ours[&missing_shard]
+                .as_ref()
+                .unwrap()
+                .attesting_validator_indices
+        );
+        assert!(diffs[1].attesters_only_theirs.is_empty());
+    }
+
+    #[test]
+    fn winning_root_owned_returns_attestation_indices() {
+        let spec = ChainSpec::few_validators();
+        let mut state = get_state(&spec);
+
+        let original = state.previous_epoch_attestations[0].clone();
+        let shard = original.data.shard;
+
+        // A competing attestation which does not win.
+        let mut loser = original.clone();
+        loser.data.crosslink_data_root = Hash256::from_slice(&[0xff; 32]);
+        loser.aggregation_bitfield = Bitfield::from_elem(loser.aggregation_bitfield.len(), false);
+        loser.aggregation_bitfield.set(0, true);
+        state.previous_epoch_attestations.push(loser);
+
+        let attestations: Vec<PendingAttestation> = state
+            .previous_epoch_attestations
+            .iter()
+            .chain(state.current_epoch_attestations.iter())
+            .cloned()
+            .collect();
+
+        let winner = winning_root(&state, shard, &spec).unwrap().unwrap();
+        let indices = winning_root_owned(&state, shard, &attestations, &spec)
+            .unwrap()
+            .unwrap();
+
+        assert!(!indices.is_empty());
+        for &i in &indices {
+            assert_eq!(attestations[i].data.shard, shard);
+            assert_eq!(
+                attestations[i].data.crosslink_data_root,
+                winner.crosslink_data_root
+            );
+        }
+        assert_eq!(
+            indices.len(),
+            attestations
+                .iter()
+                .filter(|a| (a.data.shard == shard)
+                    && (a.data.crosslink_data_root == winner.crosslink_data_root))
+                .count()
+        );
+
+        assert_eq!(
+            winning_root_owned(&state, spec.shard_count, &attestations, &spec),
+            Ok(None)
+        );
+    }
+
+    #[test]
+    fn validate_inputs_rejects_overlap() {
+        let spec = ChainSpec::few_validators();
+        let state = get_state(&spec);
+
+        let previous = &state.previous_epoch_attestations;
+        let current = &state.current_epoch_attestations;
+        assert_eq!(validate_inputs(&state, previous, current, &spec), Ok(()));
+
+        let mut overlapping = current.clone();
+        overlapping.push(previous[0].clone());
+        assert_eq!(
+            validate_inputs(&state, previous, &overlapping, &spec),
+            Err(BeaconStateError::AttestationInBothEpochs {
+                slot: previous[0].data.slot
+            })
+        );
+
+        assert_eq!(
+            validate_inputs(&state, current, current, &spec),
+            Err(BeaconStateError::AttestationInBothEpochs {
+                slot: current[0].data.slot
+            })
+        );
+    }
+
+    #[test]
+    fn winning_root_rejects_overlap_in_all_builds() {
+        let spec = ChainSpec::few_validators();
+        let state = get_state(&spec);
+
+        // The overlapping attestation is not in the current epoch, which is detected (with the
+        // same error) regardless of the build profile.
+        let a = &state.previous_epoch_attestations[0];
+        let mut overlapping = state.current_epoch_attestations.clone();
+        overlapping.push(a.clone());
+
+        assert_eq!(
+            winning_root_for_attestations(
+                &state,
+                a.data.shard,
+                &state.previous_epoch_attestations,
+                &overlapping,
+                &mut AttestationDataCache::new(),
+                &spec
+            ),
+            Err(BeaconStateError::AttestationOutOfEpoch {
+                slot: a.data.slot,
+                epoch: state.current_epoch(&spec),
+            })
+        );
+    }
+
+    #[test]
+    fn winning_root_with_epoch_attesters_partitions() {
+        let spec = ChainSpec::few_validators();
+        let mut state = get_state_with_validators(64, &spec);
+
+        let shard = state.previous_epoch_attestations[0].data.shard;
+        let root = Hash256::from_slice(&[1; 32]);
+        for a in state
+            .previous_epoch_attestations
+            .iter_mut()
+            .chain(state.current_epoch_attestations.iter_mut())
+            .filter(|a| a.data.shard == shard)
+        {
+            a.data.crosslink_data_root = root;
+        }
+
+        // Ensure the first previous epoch attester did not also attest in the current epoch.
+        let previous = &state.previous_epoch_attestations[0];
+        let only_previous = get_attestation_participants(
+            &state,
+            &previous.data,
+            &previous.aggregation_bitfield,
+            &spec,
+        )
+        .unwrap()[0];
+        for i in 0..state.current_epoch_attestations.len() {
+            let a = &state.current_epoch_attestations[i];
+            if a.data.shard != shard {
+                continue;
+            }
+            let committee = state
+                .get_crosslink_committees_at_slot(a.data.slot, &spec)
+                .unwrap()
+                .iter()
+                .find(|c| c.shard == shard)
+                .unwrap()
+                .committee
+                .clone();
+            if let Some(position) = committee.iter().position(|v| *v == only_previous) {
+                state.current_epoch_attestations[i]
+                    .aggregation_bitfield
+                    .set(position, false);
+            }
+        }
+
+        let (winner, attesters) = winning_root_with_epoch_attesters(&state, shard, &spec)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(winner.crosslink_data_root, root);
+        assert!(attesters.previous_epoch_attesters.contains(&only_previous));
+        assert!(!attesters.current_epoch_attesters.contains(&only_previous));
+        assert!(!attesters.current_epoch_attesters.is_empty());
+
+        let mut union: Vec<usize> = attesters
+            .previous_epoch_attesters
+            .iter()
+            .chain(attesters.current_epoch_attesters.iter())
+            .cloned()
+            .collect();
+        union.sort_unstable();
+        union.dedup();
+        assert_eq!(union, winner.attesting_validator_indices);
+        assert_eq!(
+            winner.total_attesting_balance,
+            union.len() as u64 * spec.max_deposit_amount
+        );
+    }
+
+    #[test]
+    fn active_shards_match_committees() {
+        let spec = ChainSpec::few_validators();
+        let state = get_state(&spec);
+
+        for epoch in &[state.previous_epoch(&spec), state.current_epoch(&spec)] {
+            let mut expected = vec![];
+            for slot in epoch.slot_iter(spec.slots_per_epoch) {
+                for c in state.get_crosslink_committees_at_slot(slot, &spec).unwrap() {
+                    assert!(c.shard < spec.shard_count);
+                    expected.push(c.shard);
+                }
+            }
+            expected.sort_unstable();
+
+            assert_eq!(active_shards_for_epoch(&state, *epoch, &spec), Ok(expected));
+        }
+
+        // Each committee of the previous epoch attested, every attested shard is active.
+        let active = active_shards_for_epoch(&state, state.previous_epoch(&spec), &spec).unwrap();
+        for a in &state.previous_epoch_attestations {
+            assert!(active.contains(&a.data.shard));
+        }
+    }
+
+    #[test]
+    fn winning_root_excluding_slashed_attester() {
+        let spec = ChainSpec::few_validators();
+
+        // Root 1 has two attesters, root 2 has one.
+        let (mut state, shard) =
+            state_with_attestations(64, &[(root(1), vec![0, 1]), (root(2), vec![2])], &spec);
+        let committee = state
+            .get_crosslink_committee_for_shard(state.previous_epoch(&spec), shard, &spec)
+            .unwrap()
+            .committee
+            .clone();
+
+        let excluding_slashed = |state: &BeaconState, exclude_slashed| {
+            let options = WinningRootOptions {
+                exclude_slashed,
+                ..WinningRootOptions::default()
+            };
+            winning_root_with_options(state, shard, &options, &spec)
+        };
+
+        let winner = winning_root(&state, shard, &spec).unwrap().unwrap();
+        assert_eq!(winner.crosslink_data_root, root(1));
+        assert_eq!(excluding_slashed(&state, true), Ok(Some(winner.clone())));
+
+        // Both attesters of root 1 are slashed, root 2 wins.
+        state.validator_registry[committee[0]].slashed = true;
+        state.validator_registry[committee[1]].slashed = true;
+        assert_eq!(excluding_slashed(&state, false), Ok(Some(winner)));
+
+        let unslashed = excluding_slashed(&state, true).unwrap().unwrap();
+        assert_eq!(unslashed.crosslink_data_root, root(2));
+        assert_eq!(unslashed.attesting_validator_indices, vec![committee[2]]);
+        assert_eq!(unslashed.total_attesting_balance, spec.max_deposit_amount);
+    }
+
+    #[test]
+    fn epoch_crosslink_stats() {
+        let spec = ChainSpec::few_validators();
+        let mut state = get_state_with_validators(64, &spec);
+
+        // Two contested shards, with margins of two and one attesters.
+        let contests: Vec<(PendingAttestation, Vec<(Hash256, Vec<usize>)>)> = vec![
+            (
+                state.previous_epoch_attestations[0].clone(),
+                vec![(root(1), (0..5).collect()), (root(2), (5..8).collect())],
+            ),
+            (
+                state.previous_epoch_attestations[1].clone(),
+                vec![(root(1), (0..4).collect()), (root(2), (4..7).collect())],
+            ),
+        ];
+        for (template, candidates) in &contests {
+            set_shard_attestations(&mut state, template, candidates);
+        }
+
+        let stats = EpochCrosslinkStats::new(&state, &spec).unwrap();
+
+        let winners: Vec<WinningRoot> = all_winning_roots(&state, None, &spec)
+            .unwrap()
+            .into_iter()
+            .filter_map(|(_, winning_root)| winning_root)
+            .collect();
+        assert!(winners.len() > 2);
+        assert_eq!(stats.shards_with_winner, winners.len());
+        assert_eq!(
+            stats.total_attesting_balance,
+            winners
+                .iter()
+                .map(|w| w.total_attesting_balance)
+                .sum::<u64>()
+        );
+        assert_eq!(
+            stats.closest_contest,
+            Some((contests[1].0.data.shard, spec.max_deposit_amount))
+        );
+    }
+
+    #[test]
+    fn attestation_out_of_epoch() {
+        let spec = ChainSpec::few_validators();
+        let mut state = get_state(&spec);
+
+        let a = state.previous_epoch_attestations.pop().unwrap();
+        let shard = a.data.shard;
+        let slot = a.data.slot;
+        state.current_epoch_attestations.push(a);
+
+        assert_eq!(
+            winning_root(&state, shard, &spec),
+            Err(BeaconStateError::AttestationOutOfEpoch {
+                slot,
+                epoch: state.current_epoch(&spec),
+            })
+        );
+    }
+
+    /// A single case of `test_vectors/winning_root.json`.
+    #[derive(Deserialize)]
+    struct WinningRootVector {
+        name: String,
+        validator_count: usize,
+        /// The slot (relative to the start of the previous epoch) of the attesting committee.
+        slot_offset: u64,
+        attestations: Vec<VectorAttestation>,
+        expected: Option<VectorAttestation>,
+    }
+
+    /// An attestation for `crosslink_data_root`, by the given positions of the committee.
+    #[derive(Deserialize)]
+    struct VectorAttestation {
+        crosslink_data_root: Hash256,
+        participants: Vec<usize>,
+    }
+
+    /// Loads the test cases of `test_vectors/winning_root.json`.
+    ///
+    /// These are hand-written local fixtures, they are not from the spec test suite.
+    fn load_winning_root_vectors() -> Vec<WinningRootVector> {
+        #[derive(Deserialize)]
+        struct Document {
+            test_cases: Vec<WinningRootVector>,
+        }
+
+        let mut file_path_buf = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        file_path_buf.push("src/per_epoch_processing/test_vectors/winning_root.json");
+        let file = File::open(file_path_buf).unwrap();
+
+        let document: Document = serde_json::from_reader(file).unwrap();
+        document.test_cases
+    }
+
+    #[test]
+    fn winning_root_vectors() {
+        let spec = ChainSpec::few_validators();
+
+        let vectors = load_winning_root_vectors();
+        assert!(!vectors.is_empty());
+
+        for vector in vectors {
+            let mut state = get_state_with_validators(vector.validator_count, &spec);
+
+            let slot =
+                state.previous_epoch(&spec).start_slot(spec.slots_per_epoch) + vector.slot_offset;
+            let c = state.get_crosslink_committees_at_slot(slot, &spec).unwrap()[0].clone();
+
+            // Replace the attestations of the committee with those of the vector.
+            let template = state
+                .previous_epoch_attestations
+                .iter()
+                .find(|a| a.data.slot == slot && a.data.shard == c.shard)
+                .cloned()
+                .unwrap();
+            let attestations: Vec<(Hash256, Vec<usize>)> = vector
+                .attestations
+                .iter()
+                .map(|a| (a.crosslink_data_root, a.participants.clone()))
+                .collect();
+            set_shard_attestations(&mut state, &template, &attestations);
+
+            let winner = winning_root(&state, c.shard, &spec).unwrap();
+
+            match vector.expected {
+                Some(expected) => {
+                    let winner = winner.unwrap_or_else(|| panic!("{}: no winner", vector.name));
+                    let mut indices: Vec<usize> = expected
+                        .participants
+                        .iter()
+                        .map(|&position| c.committee[position])
+                        .collect();
+                    indices.sort_unstable();
+
+                    assert_eq!(
+                        winner.crosslink_data_root, expected.crosslink_data_root,
+                        "{}",
+                        vector.name
+                    );
+                    assert_eq!(
+                        winner.attesting_validator_indices, indices,
+                        "{}",
+                        vector.name
+                    );
+                    assert_eq!(
+                        winner.total_attesting_balance,
+                        indices.len() as u64 * spec.max_deposit_amount,
+                        "{}",
+                        vector.name
+                    );
+                }
+                None => assert_eq!(winner, None, "{}", vector.name),
+            }
+        }
     }
 }
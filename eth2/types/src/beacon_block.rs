@@ -61,7 +61,7 @@ impl BeaconBlock {
     ///
     /// Spec v0.5.0
     pub fn canonical_root(&self) -> Hash256 {
-        Hash256::from_slice(&self.hash_tree_root()[..])
+        self.tree_hash_root()
     }
 
     /// Returns a full `BeaconBlockHeader` of this block.
@@ -36,7 +36,7 @@ impl BeaconBlockHeader {
     ///
     /// Spec v0.5.0
     pub fn canonical_root(&self) -> Hash256 {
-        Hash256::from_slice(&self.hash_tree_root()[..])
+        self.tree_hash_root()
     }
 
     /// Given a `body`, consumes `self` and returns a complete `BeaconBlock`.
@@ -1,3 +1,4 @@
+use self::balance_cache::BalanceCache;
 use self::epoch_cache::{get_active_validator_indices, EpochCache, Error as EpochCacheError};
 use crate::test_utils::TestRandom;
 use crate::*;
@@ -9,6 +10,7 @@ use ssz::{hash, ssz_encode, TreeHash};
 use ssz_derive::{Decode, Encode, TreeHash};
 use test_random_derive::TestRandom;
 
+mod balance_cache;
 mod epoch_cache;
 mod pubkey_cache;
 mod tests;
@@ -31,7 +33,36 @@ pub enum Error {
     InsufficientCommittees,
     InsufficientSlashedBalances,
     InsufficientStateRoots,
-    NoCommitteeForShard,
+    /// There is no committee for the `shard` at the given `slot`.
+    NoCommitteeForShard {
+        shard: Shard,
+        slot: Slot,
+    },
+    /// There is no committee for the `shard` at any slot in the given `epoch`.
+    NoCommitteeForShardInEpoch {
+        shard: Shard,
+        epoch: Epoch,
+    },
+    /// A pending attestation at `slot` was supplied as an attestation for a different `epoch`.
+    AttestationOutOfEpoch {
+        slot: Slot,
+        epoch: Epoch,
+    },
+    /// A pending attestation at `slot` was supplied as both a previous and current epoch
+    /// attestation.
+    AttestationInBothEpochs {
+        slot: Slot,
+    },
+    /// A winning root has a positive attesting balance but a zero `crosslink_data_root`.
+    InvalidWinningRoot,
+    /// A winning root lists `validator_index` as an attester more than once.
+    DuplicateAttestingIndex(usize),
+    /// A winning root lists `validator_index` as an attester, but it is not in any committee for
+    /// the `shard`.
+    AttesterNotInCommittee {
+        validator_index: usize,
+        shard: Shard,
+    },
     PubkeyCacheInconsistent,
     PubkeyCacheIncomplete {
         cache_len: usize,
@@ -54,7 +85,7 @@ pub struct BeaconState {
 
     // Validator registry
     pub validator_registry: Vec<Validator>,
-    pub validator_balances: Vec<u64>,
+    validator_balances: Vec<u64>,
     pub validator_registry_update_epoch: Epoch,
 
     // Randomness and committees
@@ -110,6 +141,12 @@ pub struct BeaconState {
     #[tree_hash(skip_hashing)]
     #[test_random(default)]
     pub pubkey_cache: PubkeyCache,
+    #[serde(skip)]
+    #[ssz(skip_serializing)]
+    #[ssz(skip_deserializing)]
+    #[tree_hash(skip_hashing)]
+    #[test_random(default)]
+    balance_cache: BalanceCache,
 }
 
 impl BeaconState {
@@ -183,6 +220,7 @@ impl BeaconState {
                 EpochCache::default(),
             ],
             pubkey_cache: PubkeyCache::default(),
+            balance_cache: BalanceCache::default(),
         }
     }
 
@@ -190,7 +228,7 @@ impl BeaconState {
     ///
     /// Spec v0.5.0
     pub fn canonical_root(&self) -> Hash256 {
-        Hash256::from_slice(&self.hash_tree_root()[..])
+        self.tree_hash_root()
     }
 
     pub fn historical_batch(&self) -> HistoricalBatch {
@@ -238,6 +276,32 @@ impl BeaconState {
         self.current_epoch(spec) + 1
     }
 
+    /// Returns the pending attestations for the given `relative_epoch`.
+    ///
+    /// Returns `Error::EpochOutOfBounds` for the next epoch, it cannot have any attestations.
+    pub fn get_epoch_attestations(
+        &self,
+        relative_epoch: RelativeEpoch,
+    ) -> Result<&[PendingAttestation], Error> {
+        match relative_epoch {
+            RelativeEpoch::Previous => Ok(&self.previous_epoch_attestations),
+            RelativeEpoch::Current => Ok(&self.current_epoch_attestations),
+            RelativeEpoch::NextWithRegistryChange | RelativeEpoch::NextWithoutRegistryChange => {
+                Err(Error::EpochOutOfBounds)
+            }
+        }
+    }
+
+    /// Returns the `previous_epoch_attestations`, or an empty slice if the current epoch is the
+    /// genesis epoch (there is no previous epoch at genesis).
+    pub fn previous_epoch_attestations_safe(&self, spec: &ChainSpec) -> &[PendingAttestation] {
+        if self.current_epoch(spec) == spec.genesis_epoch {
+            &[]
+        } else {
+            &self.previous_epoch_attestations
+        }
+    }
+
     /// Returns the active validator indices for the given epoch, assuming there is no validator
     /// registry update in the next epoch.
     ///
@@ -314,7 +378,7 @@ impl BeaconState {
 
         Ok(cache
             .get_crosslink_committee_for_shard(shard, spec)
-            .ok_or_else(|| Error::NoCommitteeForShard)?)
+            .ok_or_else(|| Error::NoCommitteeForShardInEpoch { shard, epoch })?)
     }
 
     /// Returns the beacon proposer index for the `slot`.
@@ -605,6 +669,15 @@ impl BeaconState {
         validator_index: usize,
         spec: &ChainSpec,
     ) -> Result<u64, Error> {
+        if self.effective_balance_cache_is_current(spec) {
+            return self
+                .balance_cache
+                .effective_balances
+                .get(validator_index)
+                .cloned()
+                .ok_or_else(|| Error::UnknownValidator);
+        }
+
         let balance = self
             .validator_balances
             .get(validator_index)
@@ -774,6 +847,52 @@ impl BeaconState {
     pub fn drop_pubkey_cache(&mut self) {
         self.pubkey_cache = PubkeyCache::default()
     }
+
+    /// Returns the balance of each validator.
+    pub fn validator_balances(&self) -> &[u64] {
+        &self.validator_balances
+    }
+
+    /// Returns a mutable reference to `validator_balances`, incrementing `balances_generation`.
+    ///
+    /// This is the only way to modify `validator_balances`, so the `effective_balance_cache` is
+    /// always known to be stale afterwards.
+    pub fn validator_balances_mut(&mut self) -> &mut Vec<u64> {
+        self.balance_cache.generation = self.balance_cache.generation.wrapping_add(1);
+        &mut self.validator_balances
+    }
+
+    /// Returns a counter which changes each time `validator_balances` may have been modified.
+    pub fn balances_generation(&self) -> u64 {
+        self.balance_cache.generation
+    }
+
+    /// Rebuilds the `effective_balance_cache` from the current `validator_balances`.
+    ///
+    /// Whilst the cache is current (see `effective_balance_cache_is_current`) it is used by
+    /// `get_effective_balance`.
+    pub fn build_effective_balance_cache(&mut self, spec: &ChainSpec) {
+        self.balance_cache.effective_balances = self
+            .validator_balances
+            .iter()
+            .map(|balance| std::cmp::min(*balance, spec.max_deposit_amount))
+            .collect();
+        self.balance_cache.key = (self.balance_cache.generation, spec.max_deposit_amount);
+    }
+
+    /// Returns `true` if the `effective_balance_cache` was built from the current
+    /// `validator_balances` with the `max_deposit_amount` of `spec`, i.e., no balance has been
+    /// modified since the cache was built.
+    pub fn effective_balance_cache_is_current(&self, spec: &ChainSpec) -> bool {
+        (self.balance_cache.effective_balances.len() == self.validator_balances.len())
+            && (self.balance_cache.key == (self.balance_cache.generation, spec.max_deposit_amount))
+    }
+
+    /// Completely drops the `effective_balance_cache`, `get_effective_balance` will read directly
+    /// from `validator_balances`.
+    pub fn drop_effective_balance_cache(&mut self) {
+        self.balance_cache.effective_balances = vec![]
+    }
 }
 
 impl From<RelativeEpochError> for Error {
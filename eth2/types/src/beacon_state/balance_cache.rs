@@ -0,0 +1,21 @@
+/// Caches the effective balance of each validator and tracks modifications to
+/// `validator_balances`.
+///
+/// This is bookkeeping only, it is not part of the consensus state. Any two caches compare equal
+/// so that two `BeaconState` with equal fields are equal regardless of how their balances were
+/// modified or whether a cache has been built.
+#[derive(Debug, Clone, Default)]
+pub struct BalanceCache {
+    /// The effective balance of each validator.
+    pub effective_balances: Vec<u64>,
+    /// The `(generation, max_deposit_amount)` the `effective_balances` were built from.
+    pub key: (u64, u64),
+    /// A counter which changes each time `validator_balances` may have been modified.
+    pub generation: u64,
+}
+
+impl PartialEq for BalanceCache {
+    fn eq(&self, _other: &BalanceCache) -> bool {
+        true
+    }
+}
@@ -125,7 +125,7 @@ impl EpochCache {
         shard: Shard,
         spec: &ChainSpec,
     ) -> Option<&CrosslinkCommittee> {
-        if shard > self.shard_committee_indices.len() as u64 {
+        if shard >= self.shard_committee_indices.len() as u64 {
             None
         } else {
             let (slot, committee) = self.shard_committee_indices[shard as usize]?;
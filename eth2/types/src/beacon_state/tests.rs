@@ -1,6 +1,7 @@
 #![cfg(test)]
 use super::*;
 use crate::test_utils::*;
+use ssz::Decodable;
 
 ssz_tests!(BeaconState);
 
@@ -55,3 +56,146 @@ fn cache_initialization() {
     test_cache_initialization(&mut state, RelativeEpoch::NextWithRegistryChange, &spec);
     test_cache_initialization(&mut state, RelativeEpoch::NextWithoutRegistryChange, &spec);
 }
+
+#[test]
+fn get_epoch_attestations() {
+    let spec = ChainSpec::few_validators();
+    let mut builder = TestingBeaconStateBuilder::from_deterministic_keypairs(8, &spec);
+    builder.teleport_to_slot(
+        (spec.genesis_epoch + 4).end_slot(spec.slots_per_epoch),
+        &spec,
+    );
+    builder.insert_attestations(&spec);
+    let (state, _keypairs) = builder.build();
+
+    assert!(!state.previous_epoch_attestations.is_empty());
+    assert!(!state.current_epoch_attestations.is_empty());
+
+    assert_eq!(
+        state.get_epoch_attestations(RelativeEpoch::Previous),
+        Ok(&state.previous_epoch_attestations[..])
+    );
+    assert_eq!(
+        state.get_epoch_attestations(RelativeEpoch::Current),
+        Ok(&state.current_epoch_attestations[..])
+    );
+    assert_eq!(
+        state.get_epoch_attestations(RelativeEpoch::NextWithRegistryChange),
+        Err(BeaconStateError::EpochOutOfBounds)
+    );
+    assert_eq!(
+        state.get_epoch_attestations(RelativeEpoch::NextWithoutRegistryChange),
+        Err(BeaconStateError::EpochOutOfBounds)
+    );
+}
+
+#[test]
+fn effective_balance_cache() {
+    let spec = ChainSpec::few_validators();
+    let builder = TestingBeaconStateBuilder::from_deterministic_keypairs(8, &spec);
+    let (mut state, _keypairs) = builder.build();
+
+    let fresh = |state: &BeaconState| -> Vec<u64> {
+        state
+            .validator_balances()
+            .iter()
+            .map(|balance| std::cmp::min(*balance, spec.max_deposit_amount))
+            .collect()
+    };
+
+    state.build_effective_balance_cache(&spec);
+    assert_eq!(state.balance_cache.effective_balances, fresh(&state));
+
+    // Drop one validator below the maximum effective balance, then rebuild.
+    state.validator_balances_mut()[0] = spec.max_deposit_amount / 2;
+    state.validator_balances_mut()[1] = spec.max_deposit_amount * 2;
+    state.build_effective_balance_cache(&spec);
+    assert_eq!(state.balance_cache.effective_balances, fresh(&state));
+
+    for (i, expected) in fresh(&state).into_iter().enumerate() {
+        assert_eq!(state.get_effective_balance(i, &spec), Ok(expected));
+    }
+    assert_eq!(
+        state.get_effective_balance(state.validator_balances().len(), &spec),
+        Err(BeaconStateError::UnknownValidator)
+    );
+
+    state.drop_effective_balance_cache();
+    assert_eq!(
+        state.get_effective_balance(0, &spec),
+        Ok(spec.max_deposit_amount / 2)
+    );
+}
+
+#[test]
+fn effective_balance_cache_generation() {
+    let spec = ChainSpec::few_validators();
+    let builder = TestingBeaconStateBuilder::from_deterministic_keypairs(8, &spec);
+    let (mut state, _keypairs) = builder.build();
+
+    state.build_effective_balance_cache(&spec);
+    assert!(state.effective_balance_cache_is_current(&spec));
+
+    // Whilst current, the cache is read. An entry is altered directly to prove it.
+    state.balance_cache.effective_balances[0] = 0;
+    assert_eq!(state.get_effective_balance(0, &spec), Ok(0));
+
+    // A modification via `validator_balances_mut` makes the cache stale, it is not read.
+    state.validator_balances_mut()[0] = spec.max_deposit_amount / 2;
+    assert!(!state.effective_balance_cache_is_current(&spec));
+    assert_eq!(
+        state.get_effective_balance(0, &spec),
+        Ok(spec.max_deposit_amount / 2)
+    );
+
+    state.build_effective_balance_cache(&spec);
+    assert!(state.effective_balance_cache_is_current(&spec));
+
+    // The cache is not read for a spec with a different `max_deposit_amount`.
+    let mut other_spec = spec.clone();
+    other_spec.max_deposit_amount = spec.max_deposit_amount / 4;
+    assert!(!state.effective_balance_cache_is_current(&other_spec));
+    assert_eq!(
+        state.get_effective_balance(1, &other_spec),
+        Ok(other_spec.max_deposit_amount)
+    );
+}
+
+#[test]
+fn equality_ignores_balance_cache() {
+    let spec = ChainSpec::few_validators();
+    let builder = TestingBeaconStateBuilder::from_deterministic_keypairs(8, &spec);
+    let (state, _keypairs) = builder.build();
+
+    let mut modified = state.clone();
+    let balance = modified.validator_balances()[0];
+    modified.validator_balances_mut()[0] = balance;
+    modified.build_effective_balance_cache(&spec);
+    assert_ne!(modified.balances_generation(), state.balances_generation());
+    assert_eq!(modified, state);
+
+    // A decoded state has no cache but is equal to the state it was encoded from.
+    let (decoded, _): (BeaconState, usize) = <_>::ssz_decode(&ssz_encode(&modified), 0).unwrap();
+    assert_eq!(decoded, modified);
+}
+
+#[test]
+fn previous_epoch_attestations_safe() {
+    let spec = ChainSpec::few_validators();
+    let mut builder = TestingBeaconStateBuilder::from_deterministic_keypairs(8, &spec);
+    builder.teleport_to_slot(
+        (spec.genesis_epoch + 4).end_slot(spec.slots_per_epoch),
+        &spec,
+    );
+    builder.insert_attestations(&spec);
+    let (mut state, _keypairs) = builder.build();
+
+    assert!(!state.previous_epoch_attestations.is_empty());
+    assert_eq!(
+        state.previous_epoch_attestations_safe(&spec),
+        &state.previous_epoch_attestations[..]
+    );
+
+    state.slot = spec.genesis_slot;
+    assert!(state.previous_epoch_attestations_safe(&spec).is_empty());
+}
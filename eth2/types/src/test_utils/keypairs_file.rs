@@ -31,7 +31,7 @@ impl KeypairsFile for Vec<Keypair> {
 
             for keypair in keypair_batch {
                 buf.append(&mut keypair.sk.as_raw().as_bytes());
-                buf.append(&mut keypair.pk.clone().as_uncompressed_bytes());
+                buf.extend_from_slice(keypair.pk.as_uncompressed_bytes());
             }
 
             keypairs_file.write_all(&buf)?;
@@ -146,7 +146,7 @@ impl TestingBeaconStateBuilder {
 
         debug!("Importing {} existing validators...", validator_count);
         state.validator_registry = validators;
-        state.validator_balances = balances;
+        *state.validator_balances_mut() = balances;
 
         debug!("BeaconState initialized.");
 
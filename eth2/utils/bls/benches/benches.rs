@@ -0,0 +1,60 @@
+use bls::{PublicKey, SecretKey};
+use criterion::Criterion;
+use criterion::{black_box, criterion_group, criterion_main, Benchmark};
+use ssz::SszStream;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+/// Reading the uncompressed bytes of a key, these are computed by the first read and cached so
+/// later reads do not clone the raw key.
+fn uncompressed_bytes(c: &mut Criterion) {
+    let pubkey = PublicKey::from_secret_key(&SecretKey::random());
+
+    c.bench(
+        "public_key",
+        Benchmark::new("as_uncompressed_bytes", move |b| {
+            b.iter(|| black_box(pubkey.as_uncompressed_bytes().len()))
+        })
+        .sample_size(100),
+    );
+}
+
+/// Hashing a key (e.g., as a `HashMap` key), which reads the cached compressed bytes.
+fn hash(c: &mut Criterion) {
+    let pubkey = PublicKey::from_secret_key(&SecretKey::random());
+
+    c.bench(
+        "public_key",
+        Benchmark::new("hash", move |b| {
+            b.iter(|| {
+                let mut hasher = DefaultHasher::new();
+                pubkey.hash(&mut hasher);
+                black_box(hasher.finish())
+            })
+        })
+        .sample_size(100),
+    );
+}
+
+/// SSZ encoding a list of keys, the compressed bytes are written directly from the cached bytes
+/// of each key, without allocating.
+fn ssz_encode_list(c: &mut Criterion) {
+    let pubkeys: Vec<PublicKey> = (0..1_024)
+        .map(|_| PublicKey::from_secret_key(&SecretKey::random()))
+        .collect();
+
+    c.bench(
+        "public_key",
+        Benchmark::new("ssz_encode_1024_keys", move |b| {
+            b.iter(|| {
+                let mut stream = SszStream::new();
+                stream.append_vec(&pubkeys);
+                black_box(stream.drain())
+            })
+        })
+        .sample_size(10),
+    );
+}
+
+criterion_group!(benches, uncompressed_bytes, hash, ssz_encode_list);
+criterion_main!(benches);
@@ -1,5 +1,9 @@
-use super::PublicKey;
+use super::public_key::raw_from_bytes;
+use super::{AggregateSignature, Error, PublicKey};
 use bls_aggregates::AggregatePublicKey as RawAggregatePublicKey;
+use serde::de::{Deserialize, Deserializer};
+use serde::ser::{Serialize, Serializer};
+use serde_hex::{encode as hex_encode, HexVisitor};
 
 /// A BLS aggregate public key.
 ///
@@ -13,12 +17,143 @@ impl AggregatePublicKey {
         AggregatePublicKey(RawAggregatePublicKey::new())
     }
 
+    /// Instantiate an aggregate of all the `public_keys`.
+    pub fn from_public_keys(public_keys: &[&PublicKey]) -> Self {
+        let mut aggregate_public_key = Self::new();
+        for public_key in public_keys {
+            aggregate_public_key.add(public_key);
+        }
+        aggregate_public_key
+    }
+
     pub fn add(&mut self, public_key: &PublicKey) {
         self.0.add(public_key.as_raw())
     }
 
+    /// Decodes a compressed public key from `bytes` and adds it to the aggregate.
+    ///
+    /// The key is validated as per `PublicKey::from_bytes`, however no `PublicKey` is
+    /// constructed. The aggregate is unchanged if the key is invalid.
+    pub fn add_bytes(&mut self, compressed: &[u8]) -> Result<(), Error> {
+        self.0.add(&raw_from_bytes(compressed)?);
+        Ok(())
+    }
+
+    /// Verifies that `signature` is an aggregate of signatures over `message` and `domain` from
+    /// each of the keys in `self`.
+    ///
+    /// Returns `false` if `self` is the point at infinity (e.g., no keys have been added).
+    pub fn verify(&self, message: &[u8], domain: u64, signature: &AggregateSignature) -> bool {
+        !self.is_infinity() && signature.verify(message, domain, self)
+    }
+
+    /// Returns `true` if `self` is the point at infinity.
+    pub fn is_infinity(&self) -> bool {
+        let bytes = self.0.as_bytes();
+        // The compressed point at infinity has only the compression and infinity flags set.
+        bytes[0] == 0xc0 && bytes[1..].iter().all(|byte| *byte == 0)
+    }
+
     /// Returns the underlying public key.
     pub fn as_raw(&self) -> &RawAggregatePublicKey {
         &self.0
     }
 }
+
+impl PartialEq for AggregatePublicKey {
+    fn eq(&self, other: &AggregatePublicKey) -> bool {
+        self.0.as_bytes() == other.0.as_bytes()
+    }
+}
+
+impl Serialize for AggregatePublicKey {
+    /// Serializes the compressed point as a hex string.
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&hex_encode(self.0.as_bytes()))
+    }
+}
+
+impl<'de> Deserialize<'de> for AggregatePublicKey {
+    /// Deserializes a hex string of the compressed point.
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let bytes = deserializer.deserialize_str(HexVisitor)?;
+        // The compressed form of an aggregate is identical to that of a single public key.
+        let pubkey = PublicKey::from_bytes(&bytes[..])
+            .map_err(|e| serde::de::Error::custom(format!("invalid pubkey ({:?})", e)))?;
+
+        let mut aggregate_public_key = AggregatePublicKey::new();
+        aggregate_public_key.add(&pubkey);
+        Ok(aggregate_public_key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{Keypair, Signature};
+    use super::*;
+
+    #[test]
+    pub fn test_verify() {
+        let keypairs: Vec<Keypair> = (0..3).map(|_| Keypair::random()).collect();
+        let message = vec![42; 32];
+
+        let mut aggregate_public_key = AggregatePublicKey::new();
+        let mut aggregate_signature = AggregateSignature::new();
+        for keypair in &keypairs {
+            aggregate_public_key.add(&keypair.pk);
+            aggregate_signature.add(&Signature::new(&message, 1, &keypair.sk));
+        }
+
+        assert!(aggregate_public_key.verify(&message, 1, &aggregate_signature));
+        assert!(!AggregatePublicKey::new().verify(&message, 1, &aggregate_signature));
+    }
+
+    #[test]
+    pub fn test_add_bytes() {
+        let keypairs: Vec<Keypair> = (0..4).map(|_| Keypair::random()).collect();
+        let public_keys: Vec<&PublicKey> = keypairs.iter().map(|kp| &kp.pk).collect();
+
+        let mut aggregate_public_key = AggregatePublicKey::new();
+        for public_key in &public_keys {
+            aggregate_public_key
+                .add_bytes(public_key.as_bytes())
+                .unwrap();
+        }
+
+        assert_eq!(
+            aggregate_public_key,
+            AggregatePublicKey::from_public_keys(&public_keys)
+        );
+
+        let unchanged = aggregate_public_key.clone();
+        assert_eq!(
+            aggregate_public_key.add_bytes(&[0xff; 48]),
+            Err(Error::InvalidBytes)
+        );
+        assert_eq!(aggregate_public_key, unchanged);
+    }
+
+    #[test]
+    #[cfg(not(debug_assertions))]
+    pub fn test_verify_missing_signature() {
+        let keypairs: Vec<Keypair> = (0..3).map(|_| Keypair::random()).collect();
+        let message = vec![42; 32];
+
+        let mut aggregate_public_key = AggregatePublicKey::new();
+        let mut aggregate_signature = AggregateSignature::new();
+        for keypair in &keypairs {
+            aggregate_public_key.add(&keypair.pk);
+        }
+        for keypair in &keypairs[1..] {
+            aggregate_signature.add(&Signature::new(&message, 1, &keypair.sk));
+        }
+
+        assert!(!aggregate_public_key.verify(&message, 1, &aggregate_signature));
+    }
+}
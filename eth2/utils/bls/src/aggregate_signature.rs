@@ -1,4 +1,4 @@
-use super::{AggregatePublicKey, Signature, BLS_AGG_SIG_BYTE_SIZE};
+use super::{AggregatePublicKey, PublicKey, Signature, BLS_AGG_SIG_BYTE_SIZE};
 use bls_aggregates::{
     AggregatePublicKey as RawAggregatePublicKey, AggregateSignature as RawAggregateSignature,
 };
@@ -29,6 +29,25 @@ impl AggregateSignature {
         }
     }
 
+    /// Instantiate a new AggregateSignature from `signatures`, adding them in ascending order of
+    /// their compressed bytes.
+    ///
+    /// Aggregation is order-independent, however sorting guarantees an identical internal state
+    /// for the same set of signatures, regardless of the order they are supplied in.
+    pub fn from_signatures_sorted(signatures: &[&Signature]) -> Self {
+        let mut sorted: Vec<(Vec<u8>, &Signature)> = signatures
+            .iter()
+            .map(|sig| (sig.as_bytes(), *sig))
+            .collect();
+        sorted.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let mut aggregate_signature = Self::new();
+        for (_, signature) in sorted {
+            aggregate_signature.add(signature);
+        }
+        aggregate_signature
+    }
+
     /// Add (aggregate) a signature to the `AggregateSignature`.
     pub fn add(&mut self, signature: &Signature) {
         if !self.is_empty {
@@ -59,6 +78,33 @@ impl AggregateSignature {
             .verify(msg, domain, aggregate_public_key.as_raw())
     }
 
+    /// Verifies that `self` is an aggregate of signatures over `msg` and `domain` from each of
+    /// `public_keys`.
+    ///
+    /// An aggregate of zero signers is never valid (e.g., for an attestation), so this returns
+    /// `false` if `public_keys` is empty, even for the infinity signature (i.e.,
+    /// `AggregateSignature::new()`). The infinity signature is also rejected for any non-empty
+    /// `public_keys`, as are keys which aggregate to the point at infinity.
+    pub fn fast_aggregate_verify(
+        &self,
+        msg: &[u8],
+        domain: u64,
+        public_keys: &[&PublicKey],
+    ) -> bool {
+        if public_keys.is_empty() || self.is_infinity() {
+            return false;
+        }
+
+        AggregatePublicKey::from_public_keys(public_keys).verify(msg, domain, self)
+    }
+
+    /// Returns `true` if the signature is the point at infinity, the aggregate of zero signatures.
+    fn is_infinity(&self) -> bool {
+        let bytes = self.aggregate_signature.as_bytes();
+        // The compressed point at infinity has only the compression and infinity flags set.
+        bytes[0] == 0xc0 && bytes[1..].iter().all(|byte| *byte == 0)
+    }
+
     /// Verify this AggregateSignature against multiple AggregatePublickeys with multiple Messages.
     ///
     ///  All PublicKeys related to a Message should be aggregated into one AggregatePublicKey.
@@ -85,6 +131,34 @@ impl AggregateSignature {
             .verify_multiple(&msg[..], domain, &aggregate_public_keys[..])
     }
 
+    /// Verify this AggregateSignature against the `(public_key, message)` of each signer, with a
+    /// single multi-pairing.
+    ///
+    /// Signers may sign identical messages. Returns `false` if `pairs` is empty or the messages
+    /// differ in length.
+    pub fn verify_pairs(&self, pairs: &[(&PublicKey, &[u8])], domain: u64) -> bool {
+        let message_len = match pairs.first() {
+            Some((_, message)) => message.len(),
+            None => return false,
+        };
+        if pairs
+            .iter()
+            .any(|(_, message)| message.len() != message_len)
+        {
+            return false;
+        }
+
+        let aggregate_public_keys: Vec<AggregatePublicKey> = pairs
+            .iter()
+            .map(|(public_key, _)| AggregatePublicKey::from_public_keys(&[*public_key]))
+            .collect();
+        let aggregate_public_keys: Vec<&AggregatePublicKey> =
+            aggregate_public_keys.iter().collect();
+        let messages: Vec<&[u8]> = pairs.iter().map(|(_, message)| *message).collect();
+
+        self.verify_multiple(&messages, domain, &aggregate_public_keys)
+    }
+
     /// Return AggregateSiganture as bytes
     pub fn as_bytes(&self) -> Vec<u8> {
         if self.is_empty {
@@ -189,4 +263,20 @@ mod tests {
 
         assert_eq!(original, decoded);
     }
+
+    #[test]
+    pub fn test_from_signatures_sorted() {
+        let signatures: Vec<Signature> = (0..4)
+            .map(|_| Signature::new(&[42, 42], 0, &Keypair::random().sk))
+            .collect();
+
+        let forwards: Vec<&Signature> = signatures.iter().collect();
+        let backwards: Vec<&Signature> = signatures.iter().rev().collect();
+
+        let a = AggregateSignature::from_signatures_sorted(&forwards);
+        let b = AggregateSignature::from_signatures_sorted(&backwards);
+
+        assert_eq!(ssz_encode(&a), ssz_encode(&b));
+        assert_eq!(a, b);
+    }
 }
@@ -0,0 +1,14 @@
+/// An error when constructing a BLS type from bytes.
+///
+/// Distinct from `ssz::DecodeError`, which is only returned at the SSZ boundary.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Error {
+    /// The bytes are not a valid encoding of a point on the curve.
+    InvalidBytes,
+    /// The bytes are not the length required by the encoding.
+    WrongLength { expected: usize, got: usize },
+    /// The point is the point at infinity, where this is not permitted.
+    InfinityPoint,
+    /// The compressed bytes of a point do not decode to the point itself.
+    EncodingMismatch,
+}
@@ -1,4 +1,4 @@
-use super::{fake_signature::FakeSignature, AggregatePublicKey, BLS_AGG_SIG_BYTE_SIZE};
+use super::{fake_signature::FakeSignature, AggregatePublicKey, PublicKey, BLS_AGG_SIG_BYTE_SIZE};
 use serde::de::{Deserialize, Deserializer};
 use serde::ser::{Serialize, Serializer};
 use serde_hex::{encode as hex_encode, PrefixedHexVisitor};
@@ -26,6 +26,11 @@ impl FakeAggregateSignature {
         }
     }
 
+    /// Creates a new all-zero's signature
+    pub fn from_signatures_sorted(_signatures: &[&FakeSignature]) -> Self {
+        Self::zero()
+    }
+
     /// Does glorious nothing.
     pub fn add(&mut self, _signature: &FakeSignature) {
         // Do nothing.
@@ -46,6 +51,17 @@ impl FakeAggregateSignature {
         true
     }
 
+    /// Returns `true` for any non-empty `public_keys`, an aggregate of zero signers is never
+    /// valid.
+    pub fn fast_aggregate_verify(
+        &self,
+        _msg: &[u8],
+        _domain: u64,
+        public_keys: &[&PublicKey],
+    ) -> bool {
+        !public_keys.is_empty()
+    }
+
     /// _Always_ returns `true`.
     pub fn verify_multiple(
         &self,
@@ -55,6 +71,11 @@ impl FakeAggregateSignature {
     ) -> bool {
         true
     }
+
+    /// _Always_ returns `true`.
+    pub fn verify_pairs(&self, _pairs: &[(&PublicKey, &[u8])], _domain: u64) -> bool {
+        true
+    }
 }
 
 impl Encodable for FakeAggregateSignature {
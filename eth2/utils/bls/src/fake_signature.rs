@@ -51,6 +51,11 @@ impl FakeSignature {
     pub fn empty_signature() -> Self {
         FakeSignature::zero()
     }
+
+    /// _Always_ returns `false`, fake signatures are never considered empty.
+    pub fn is_empty(&self) -> bool {
+        false
+    }
 }
 
 impl Encodable for FakeSignature {
@@ -1,4 +1,5 @@
-use super::{PublicKey, SecretKey};
+use super::{PublicKey, SecretKey, Signature};
+use rand::{CryptoRng, RngCore};
 use serde_derive::{Deserialize, Serialize};
 use std::fmt;
 use std::hash::{Hash, Hasher};
@@ -17,6 +18,20 @@ impl Keypair {
         Keypair { sk, pk }
     }
 
+    /// Instantiate a Keypair using `SecretKey::random_with_rng`.
+    pub fn random_with_rng<R: RngCore + CryptoRng>(rng: &mut R) -> Self {
+        let sk = SecretKey::random_with_rng(rng);
+        let pk = PublicKey::from_secret_key(&sk);
+        Keypair { sk, pk }
+    }
+
+    /// Signs `message` under the given `domain` with the secret key.
+    ///
+    /// See `SecretKey::sign`.
+    pub fn sign(&self, message: &[u8], domain: u64) -> Signature {
+        self.sk.sign(message, domain)
+    }
+
     pub fn identifier(&self) -> String {
         self.pk.concatenated_hex_id()
     }
@@ -24,7 +39,7 @@ impl Keypair {
 
 impl PartialEq for Keypair {
     fn eq(&self, other: &Keypair) -> bool {
-        self == other
+        (self.pk == other.pk) && (self.sk == other.sk)
     }
 }
 
@@ -45,3 +60,20 @@ impl fmt::Display for Keypair {
         write!(f, "{}", self.pk)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::prng::ChaChaRng;
+    use rand::SeedableRng;
+
+    #[test]
+    pub fn test_random_with_rng_is_reproducible() {
+        let a = Keypair::random_with_rng(&mut ChaChaRng::from_seed([42; 32]));
+        let b = Keypair::random_with_rng(&mut ChaChaRng::from_seed([42; 32]));
+        assert_eq!(a, b);
+
+        let c = Keypair::random_with_rng(&mut ChaChaRng::from_seed([43; 32]));
+        assert_ne!(a, c);
+    }
+}
@@ -1,9 +1,19 @@
+//! BLS12-381 keys and signatures, wrapping the types of the `bls_aggregates` crate.
+//!
+//! The underlying library does not expose its field, scalar or curve arithmetic (e.g., hashing a
+//! message to a curve point, or the pairing itself). Functions here which operate on many keys or
+//! signatures decode or verify each of them in turn, they are not batched.
+
 extern crate bls_aggregates;
 extern crate ssz;
 
 mod aggregate_public_key;
+mod error;
 mod keypair;
 mod public_key;
+mod public_key_pool;
+#[cfg(feature = "threshold-keys")]
+mod scalar;
 mod secret_key;
 
 #[cfg(not(debug_assertions))]
@@ -25,27 +35,134 @@ pub use crate::fake_aggregate_signature::FakeAggregateSignature as AggregateSign
 pub use crate::fake_signature::FakeSignature as Signature;
 
 pub use crate::aggregate_public_key::AggregatePublicKey;
+pub use crate::error::Error;
 pub use crate::keypair::Keypair;
-pub use crate::public_key::PublicKey;
+pub use crate::public_key::{PublicKey, VerifyError};
+pub use crate::public_key_pool::PublicKeyPool;
 pub use crate::secret_key::SecretKey;
 
+/// The underlying types, as returned by the `as_raw` methods.
+///
+/// Intended for operations which are not (yet) provided by the wrapper types.
+pub use bls_aggregates::{
+    AggregatePublicKey as RawAggregatePublicKey, AggregateSignature as RawAggregateSignature,
+    PublicKey as RawPublicKey, SecretKey as RawSecretKey, Signature as RawSignature,
+};
+
 pub const BLS_AGG_SIG_BYTE_SIZE: usize = 96;
 pub const BLS_SIG_BYTE_SIZE: usize = 96;
 pub const BLS_SECRET_KEY_BYTE_SIZE: usize = 48;
 pub const BLS_PUBLIC_KEY_BYTE_SIZE: usize = 48;
+pub const BLS_PUBLIC_KEY_UNCOMPRESSED_BYTE_SIZE: usize = 96;
 
-use hashing::hash;
-use ssz::ssz_encode;
+use ethereum_types::H256;
+use hashing::{hash, hash_fixed};
+use ssz::TreeHash;
 
 /// Returns the withdrawal credentials for a given public key.
 pub fn get_withdrawal_credentials(pubkey: &PublicKey, prefix_byte: u8) -> Vec<u8> {
-    let hashed = hash(&ssz_encode(pubkey));
+    let hashed = pubkey.key_commitment();
     let mut prefixed = vec![prefix_byte];
-    prefixed.extend_from_slice(&hashed[1..]);
+    prefixed.extend_from_slice(&hashed.as_bytes()[1..]);
 
     prefixed
 }
 
+/// Returns the signing root of an object, `hash(object_root || domain)` with the `domain` as eight
+/// little-endian bytes right-padded to 32 bytes.
+///
+/// The `hash` is the Keccak-256 of this crate, so the result is _not_ the signing root of later
+/// spec versions (which use SHA-256 and a 32-byte domain). `sign`/`verify` keep the spec v0.5.0
+/// semantics and do not use this function, the `domain` is passed to the hash-to-curve function
+/// rather than mixed into the message.
+pub fn compute_signing_root(object_root: H256, domain: u64) -> H256 {
+    let mut preimage = [0; 64];
+    preimage[..32].copy_from_slice(object_root.as_bytes());
+    preimage[32..40].copy_from_slice(&domain.to_le_bytes());
+
+    H256::from(hash_fixed(&preimage))
+}
+
+/// Signs the `hash` of `message` (the 32-byte signing root) under `domain`.
+///
+/// Callers signing anything other than an existing 32-byte root must use this function (and
+/// `verify_message`) so that the signer and verifier always agree on the signed bytes.
+pub fn sign_message(secret_key: &SecretKey, message: &[u8], domain: u64) -> Signature {
+    secret_key.sign(&hash(message), domain)
+}
+
+/// Verifies a `signature` produced by `sign_message`.
+pub fn verify_message(
+    pubkey: &PublicKey,
+    message: &[u8],
+    domain: u64,
+    signature: &Signature,
+) -> bool {
+    signature.verify(&hash(message), domain, pubkey)
+}
+
+/// Signs the `hash_tree_root` of `object` under `domain`.
+///
+/// Prefer this to signing a root computed by the caller, it ensures the signer and verifier (see
+/// `verify_object`) always hash the same bytes.
+pub fn sign_object<T: TreeHash>(secret_key: &SecretKey, object: &T, domain: u64) -> Signature {
+    secret_key.sign(&object.hash_tree_root(), domain)
+}
+
+/// Verifies a `signature` produced by `sign_object`.
+pub fn verify_object<T: TreeHash>(
+    pubkey: &PublicKey,
+    object: &T,
+    domain: u64,
+    signature: &Signature,
+) -> bool {
+    signature.verify(&object.hash_tree_root(), domain, pubkey)
+}
+
+/// Verifies the proof-of-possession of each `(pubkey, signature, signed_root)` of a batch of
+/// deposits (e.g., when constructing a genesis state), where `signed_root` is the signed root of
+/// the deposit data.
+///
+/// Returns the index of the first invalid deposit.
+///
+/// The deposits are verified one at a time, in order, and verification stops at the first invalid
+/// deposit. The cost is that of verifying each signature separately.
+pub fn verify_genesis_deposits(
+    deposits: &[(PublicKey, Signature, H256)],
+    domain: u64,
+) -> Result<(), usize> {
+    match deposits
+        .iter()
+        .position(|(pubkey, signature, signed_root)| {
+            !signature.verify(signed_root.as_bytes(), domain, pubkey)
+        }) {
+        Some(index) => Err(index),
+        None => Ok(()),
+    }
+}
+
+/// Aggregates the public keys and signatures of `pairs` in a single pass, e.g., when producing
+/// an aggregate attestation from the signatures of each committee member.
+///
+/// Returns `None` if `pairs` is empty, an aggregate of zero signers is never valid.
+pub fn aggregate_attestation_signatures(
+    pairs: &[(&PublicKey, &Signature)],
+) -> Option<(AggregatePublicKey, AggregateSignature)> {
+    if pairs.is_empty() {
+        return None;
+    }
+
+    let mut aggregate_public_key = AggregatePublicKey::new();
+    let mut aggregate_signature = AggregateSignature::new();
+
+    for (public_key, signature) in pairs {
+        aggregate_public_key.add(public_key);
+        aggregate_signature.add(signature);
+    }
+
+    Some((aggregate_public_key, aggregate_signature))
+}
+
 pub fn bls_verify_aggregate(
     pubkey: &AggregatePublicKey,
     message: &[u8],
@@ -54,3 +171,321 @@ pub fn bls_verify_aggregate(
 ) -> bool {
     signature.verify(message, domain, pubkey)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::de::DeserializeOwned;
+    use serde::Serialize;
+    use std::fmt::Debug;
+
+    /// Serializes `value` to JSON and back, asserting that the result is unchanged.
+    fn serde_round_trip<T>(value: T)
+    where
+        T: Serialize + DeserializeOwned + PartialEq + Debug,
+    {
+        let json = serde_json::to_string(&value).unwrap();
+        let decoded: T = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, value);
+    }
+
+    #[test]
+    pub fn test_serde_round_trips() {
+        let keypair = Keypair::random();
+        let message = vec![42; 32];
+
+        let signature = keypair.sign(&message, 1);
+
+        let mut aggregate_signature = AggregateSignature::new();
+        aggregate_signature.add(&signature);
+
+        let mut aggregate_public_key = AggregatePublicKey::new();
+        aggregate_public_key.add(&keypair.pk);
+        aggregate_public_key.add(&Keypair::random().pk);
+
+        serde_round_trip(keypair.pk.clone());
+        serde_round_trip(keypair.sk.clone());
+        serde_round_trip(keypair.clone());
+        serde_round_trip(signature);
+        serde_round_trip(Signature::empty_signature());
+        serde_round_trip(aggregate_signature);
+        serde_round_trip(aggregate_public_key);
+    }
+
+    #[test]
+    pub fn test_sign_and_verify_message() {
+        let keypair = Keypair::random();
+        let message = vec![42; 1_024];
+
+        let signature = sign_message(&keypair.sk, &message, 1);
+
+        assert!(verify_message(&keypair.pk, &message, 1, &signature));
+    }
+
+    #[test]
+    #[cfg(not(debug_assertions))]
+    pub fn test_message_is_not_a_root() {
+        let keypair = Keypair::random();
+        let message = vec![42; 1_024];
+
+        let signature = sign_message(&keypair.sk, &message, 1);
+
+        // The raw message bytes were not signed, only their root.
+        assert!(!signature.verify(&message, 1, &keypair.pk));
+        assert!(signature.verify(&hash(&message), 1, &keypair.pk));
+    }
+
+    /// A small SSZ container, in the style of `AttestationData`.
+    struct TestObject {
+        slot: u64,
+        shard: u64,
+        root: H256,
+    }
+
+    impl TreeHash for TestObject {
+        fn hash_tree_root(&self) -> Vec<u8> {
+            let mut list: Vec<Vec<u8>> = vec![
+                self.slot.hash_tree_root(),
+                self.shard.hash_tree_root(),
+                self.root.hash_tree_root(),
+            ];
+            ssz::merkle_hash(&mut list)
+        }
+    }
+
+    #[test]
+    pub fn test_sign_and_verify_object() {
+        let keypair = Keypair::random();
+        let object = TestObject {
+            slot: 42,
+            shard: 3,
+            root: H256::from_slice(&[0x42; 32]),
+        };
+
+        let signature = sign_object(&keypair.sk, &object, 1);
+
+        assert!(verify_object(&keypair.pk, &object, 1, &signature));
+        assert!(signature.verify(&object.hash_tree_root(), 1, &keypair.pk));
+    }
+
+    #[test]
+    #[cfg(not(debug_assertions))]
+    pub fn test_verify_object_failures() {
+        let keypair = Keypair::random();
+        let object = TestObject {
+            slot: 42,
+            shard: 3,
+            root: H256::from_slice(&[0x42; 32]),
+        };
+
+        let signature = sign_object(&keypair.sk, &object, 1);
+
+        let other = TestObject { shard: 4, ..object };
+        assert!(!verify_object(&keypair.pk, &other, 1, &signature));
+        assert!(!verify_object(&keypair.pk, &object, 2, &signature));
+        assert!(!verify_object(
+            &Keypair::random().pk,
+            &object,
+            1,
+            &signature
+        ));
+    }
+
+    #[test]
+    pub fn test_compute_signing_root() {
+        let object_root = H256::from_slice(&[0x42; 32]);
+
+        let mut preimage = vec![0x42; 32];
+        preimage.extend_from_slice(&[3, 0, 0, 0, 0, 0, 0, 0]);
+        preimage.extend_from_slice(&[0; 24]);
+
+        assert_eq!(
+            compute_signing_root(object_root, 3),
+            H256::from_slice(&hash(&preimage))
+        );
+        assert_ne!(
+            compute_signing_root(object_root, 3),
+            compute_signing_root(object_root, 4)
+        );
+    }
+
+    #[test]
+    pub fn test_verify_pairs() {
+        let keypairs: Vec<Keypair> = (0..3).map(|_| Keypair::random()).collect();
+        let messages: Vec<Vec<u8>> = (0..3).map(|i| vec![i; 32]).collect();
+
+        let aggregate = |messages: &[Vec<u8>]| {
+            let mut aggregate_signature = AggregateSignature::new();
+            for (keypair, message) in keypairs.iter().zip(messages) {
+                aggregate_signature.add(&keypair.sign(message, 1));
+            }
+            aggregate_signature
+        };
+        assert!(aggregate(&messages).verify_pairs(&signer_pairs(&keypairs, &messages), 1));
+
+        // Identical messages across signers.
+        let identical = vec![vec![42; 32]; 3];
+        assert!(aggregate(&identical).verify_pairs(&signer_pairs(&keypairs, &identical), 1));
+    }
+
+    /// Pairs the public key of each of `keypairs` with the message of the same index.
+    fn signer_pairs<'a>(
+        keypairs: &'a [Keypair],
+        messages: &'a [Vec<u8>],
+    ) -> Vec<(&'a PublicKey, &'a [u8])> {
+        keypairs
+            .iter()
+            .zip(messages)
+            .map(|(keypair, message)| (&keypair.pk, message.as_slice()))
+            .collect()
+    }
+
+    #[test]
+    #[cfg(not(debug_assertions))]
+    pub fn test_verify_pairs_failures() {
+        let keypairs: Vec<Keypair> = (0..3).map(|_| Keypair::random()).collect();
+        let messages: Vec<Vec<u8>> = (0..3).map(|i| vec![i; 32]).collect();
+
+        let mut aggregate_signature = AggregateSignature::new();
+        for (keypair, message) in keypairs.iter().zip(&messages) {
+            aggregate_signature.add(&keypair.sign(message, 1));
+        }
+
+        let mut pairs = signer_pairs(&keypairs, &messages);
+        assert!(!aggregate_signature.verify_pairs(&pairs, 2));
+        assert!(!aggregate_signature.verify_pairs(&[], 1));
+
+        // A signer paired with the message of another signer.
+        pairs[0].1 = messages[1].as_slice();
+        assert!(!aggregate_signature.verify_pairs(&pairs, 1));
+    }
+
+    #[test]
+    pub fn test_fast_aggregate_verify() {
+        let keypairs: Vec<Keypair> = (0..3).map(|_| Keypair::random()).collect();
+        let public_keys: Vec<&PublicKey> = keypairs.iter().map(|keypair| &keypair.pk).collect();
+        let message = [42; 32];
+
+        let mut aggregate_signature = AggregateSignature::new();
+        for keypair in &keypairs {
+            aggregate_signature.add(&keypair.sign(&message, 1));
+        }
+        assert!(aggregate_signature.fast_aggregate_verify(&message, 1, &public_keys));
+
+        // Zero signers, with both the infinity signature and a valid aggregate.
+        assert!(!AggregateSignature::new().fast_aggregate_verify(&message, 1, &[]));
+        assert!(!aggregate_signature.fast_aggregate_verify(&message, 1, &[]));
+    }
+
+    #[test]
+    #[cfg(not(debug_assertions))]
+    pub fn test_fast_aggregate_verify_infinity_signature() {
+        let keypairs: Vec<Keypair> = (0..3).map(|_| Keypair::random()).collect();
+        let public_keys: Vec<&PublicKey> = keypairs.iter().map(|keypair| &keypair.pk).collect();
+        let message = [42; 32];
+
+        let infinity = AggregateSignature::new();
+        assert!(!infinity.fast_aggregate_verify(&message, 1, &public_keys));
+        assert!(!infinity.fast_aggregate_verify(&message, 1, &public_keys[..1]));
+
+        // A valid aggregate checked against a subset of its signers.
+        let mut aggregate_signature = AggregateSignature::new();
+        for keypair in &keypairs {
+            aggregate_signature.add(&keypair.sign(&message, 1));
+        }
+        assert!(!aggregate_signature.fast_aggregate_verify(&message, 1, &public_keys[..2]));
+    }
+
+    /// Returns a deposit from each of `keypairs`, signed under `domain`.
+    fn genesis_deposits(keypairs: &[Keypair], domain: u64) -> Vec<(PublicKey, Signature, H256)> {
+        keypairs
+            .iter()
+            .enumerate()
+            .map(|(i, keypair)| {
+                let signed_root = H256::from_slice(&[i as u8; 32]);
+                let signature = keypair.sign(signed_root.as_bytes(), domain);
+                (keypair.pk.clone(), signature, signed_root)
+            })
+            .collect()
+    }
+
+    #[test]
+    pub fn test_verify_genesis_deposits() {
+        let keypairs: Vec<Keypair> = (0..4).map(|_| Keypair::random()).collect();
+
+        assert_eq!(
+            verify_genesis_deposits(&genesis_deposits(&keypairs, 3), 3),
+            Ok(())
+        );
+        assert_eq!(verify_genesis_deposits(&[], 3), Ok(()));
+    }
+
+    #[test]
+    #[cfg(not(debug_assertions))]
+    pub fn test_verify_genesis_deposits_bad_deposit() {
+        let keypairs: Vec<Keypair> = (0..4).map(|_| Keypair::random()).collect();
+
+        // A deposit for the key of another validator.
+        let mut deposits = genesis_deposits(&keypairs, 3);
+        deposits[2].0 = keypairs[3].pk.clone();
+        assert_eq!(verify_genesis_deposits(&deposits, 3), Err(2));
+
+        // The first invalid deposit is reported.
+        deposits[1].2 = H256::from_slice(&[42; 32]);
+        assert_eq!(verify_genesis_deposits(&deposits, 3), Err(1));
+
+        assert_eq!(
+            verify_genesis_deposits(&genesis_deposits(&keypairs, 3), 4),
+            Err(0)
+        );
+    }
+
+    #[test]
+    #[cfg(not(debug_assertions))]
+    pub fn test_verify_genesis_deposits_single_bad_signature() {
+        let keypairs: Vec<Keypair> = (0..8).map(|_| Keypair::random()).collect();
+
+        for &bad_index in &[0, 5, 7] {
+            // A valid signature from the same key, over another root.
+            let mut deposits = genesis_deposits(&keypairs, 3);
+            deposits[bad_index].1 = keypairs[bad_index].sign(&[42; 32], 3);
+
+            assert_eq!(verify_genesis_deposits(&deposits, 3), Err(bad_index));
+        }
+    }
+
+    #[test]
+    pub fn test_raw_types_are_nameable() {
+        let keypair = Keypair::random();
+
+        let raw_pk: &RawPublicKey = keypair.pk.as_raw();
+        let raw_sk: &RawSecretKey = keypair.sk.as_raw();
+
+        assert_eq!(
+            raw_pk.as_bytes(),
+            RawPublicKey::from_secret_key(raw_sk).as_bytes()
+        );
+    }
+
+    #[test]
+    pub fn test_aggregate_attestation_signatures() {
+        let keypairs: Vec<Keypair> = (0..4).map(|_| Keypair::random()).collect();
+        let message = [42; 32];
+
+        let signatures: Vec<Signature> = keypairs
+            .iter()
+            .map(|keypair| keypair.sign(&message, 1))
+            .collect();
+        let pairs: Vec<(&PublicKey, &Signature)> = keypairs
+            .iter()
+            .map(|keypair| &keypair.pk)
+            .zip(signatures.iter())
+            .collect();
+
+        let (aggregate_public_key, aggregate_signature) =
+            aggregate_attestation_signatures(&pairs).unwrap();
+
+        assert!(aggregate_public_key.verify(&message, 1, &aggregate_signature));
+        assert!(aggregate_attestation_signatures(&[]).is_none());
+    }
+}
@@ -1,9 +1,20 @@
-use super::{SecretKey, BLS_PUBLIC_KEY_BYTE_SIZE};
-use bls_aggregates::PublicKey as RawPublicKey;
+use super::{
+    AggregatePublicKey, Error, SecretKey, Signature, BLS_PUBLIC_KEY_BYTE_SIZE,
+    BLS_PUBLIC_KEY_UNCOMPRESSED_BYTE_SIZE, BLS_SIG_BYTE_SIZE,
+};
+use bls_aggregates::{AggregatePublicKey as RawAggregatePublicKey, PublicKey as RawPublicKey};
+use ethereum_types::H256;
+use fnv::FnvHasher;
+use once_cell::sync::OnceCell;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 use serde::de::{Deserialize, Deserializer};
 use serde::ser::{Serialize, Serializer};
-use serde_hex::{encode as hex_encode, HexVisitor};
-use ssz::{decode, hash, ssz_encode, Decodable, DecodeError, Encodable, SszStream, TreeHash};
+use serde_hex::{encode as hex_encode, BytesVisitor, HexOrSeqVisitor};
+use ssz::{
+    decode, hash, hash_fixed, ssz_encode, BlsPointError, Decodable, DecodeError, Encodable,
+    SszStream, TreeHash,
+};
 use std::default;
 use std::fmt;
 use std::hash::{Hash, Hasher};
@@ -12,35 +23,319 @@ use std::hash::{Hash, Hasher};
 ///
 /// This struct is a wrapper upon a base type and provides helper functions (e.g., SSZ
 /// serialization).
-#[derive(Debug, Clone, Eq)]
-pub struct PublicKey(RawPublicKey);
+///
+/// The compressed bytes are computed once, when the key is created, as the raw key allocates a new
+/// `Vec` for them. The uncompressed bytes are computed on first use and then cached, as the raw key
+/// requires a mutable reference (and therefore a clone) to produce them.
+#[derive(Debug, Clone)]
+pub struct PublicKey {
+    raw: RawPublicKey,
+    compressed: [u8; BLS_PUBLIC_KEY_BYTE_SIZE],
+    uncompressed: OnceCell<Vec<u8>>,
+}
+
+/// The reason a signature failed `PublicKey::verify_with_reason`.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum VerifyError {
+    /// The signature is the empty signature, which never verifies.
+    EmptySignature,
+    /// The public key is the point at infinity.
+    InfinityPublicKey,
+    /// The signature is not valid for the message, domain and public key. This is the only
+    /// failure caused by signing under a different domain.
+    PairingCheckFailed,
+}
 
 impl PublicKey {
+    /// The length of the SSZ encoding of a `PublicKey`, identical to `BLS_PUBLIC_KEY_BYTE_SIZE`.
+    pub const SSZ_BYTES: usize = BLS_PUBLIC_KEY_BYTE_SIZE;
+
     pub fn from_secret_key(secret_key: &SecretKey) -> Self {
-        PublicKey(RawPublicKey::from_secret_key(secret_key.as_raw()))
+        PublicKey::from_raw(RawPublicKey::from_secret_key(secret_key.as_raw()))
+    }
+
+    /// Wraps `raw`, computing its compressed bytes.
+    ///
+    /// The inverse of `as_raw`, for keys computed with the raw API.
+    pub fn from_raw(raw: RawPublicKey) -> Self {
+        let mut compressed = [0; BLS_PUBLIC_KEY_BYTE_SIZE];
+        compressed.copy_from_slice(&raw.as_bytes());
+        PublicKey {
+            raw,
+            compressed,
+            uncompressed: OnceCell::new(),
+        }
+    }
+
+    /// Returns the compressed bytes of the PublicKey (identical to the SSZ encoding).
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.compressed
     }
 
     /// Returns the underlying signature.
     pub fn as_raw(&self) -> &RawPublicKey {
-        &self.0
+        &self.raw
     }
 
     /// Converts compressed bytes to PublicKey
-    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
-        let pubkey = RawPublicKey::from_bytes(&bytes).map_err(|_| DecodeError::Invalid)?;
-        Ok(PublicKey(pubkey))
+    ///
+    /// The point at infinity is permitted, see `from_bytes_non_infinity`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        Ok(PublicKey::from_raw(raw_from_bytes(bytes)?))
+    }
+
+    /// Identical to `from_bytes`, however the point at infinity is rejected.
+    ///
+    /// Use for keys which must be able to sign (e.g., validator keys). A signature from the point
+    /// at infinity is trivial to forge.
+    pub fn from_bytes_non_infinity(bytes: &[u8]) -> Result<Self, Error> {
+        let pubkey = PublicKey::from_bytes(bytes)?;
+
+        if pubkey.is_infinity() {
+            Err(Error::InfinityPoint)
+        } else {
+            Ok(pubkey)
+        }
+    }
+
+    /// Returns the sum of all `keys` as a single `PublicKey`.
+    ///
+    /// Unlike an `AggregatePublicKey`, the result may be used anywhere a `PublicKey` is expected.
+    /// An empty slice returns the point at infinity.
+    pub fn combine(keys: &[&PublicKey]) -> Self {
+        let mut aggregate = RawAggregatePublicKey::new();
+        for key in keys {
+            aggregate.add(key.as_raw());
+        }
+
+        let pubkey = RawPublicKey::from_bytes(&aggregate.as_bytes())
+            .expect("The sum of public keys is a valid public key.");
+        PublicKey::from_raw(pubkey)
+    }
+
+    /// Decodes a concatenated list of compressed public keys, aggregating them.
+    ///
+    /// Every key is decoded (and therefore validated) before it is added to the aggregate. On
+    /// failure, the index of the first invalid key is returned with the error.
+    pub fn try_aggregate_from_ssz(
+        bytes: &[u8],
+    ) -> Result<AggregatePublicKey, (usize, DecodeError)> {
+        let mut aggregate_public_key = AggregatePublicKey::new();
+
+        for (i, chunk) in bytes.chunks(BLS_PUBLIC_KEY_BYTE_SIZE).enumerate() {
+            if chunk.len() < BLS_PUBLIC_KEY_BYTE_SIZE {
+                return Err((i, DecodeError::TooShort));
+            }
+
+            let pubkey =
+                PublicKey::from_bytes(chunk).map_err(|_| (i, point_decode_error(chunk)))?;
+            aggregate_public_key.add(&pubkey);
+        }
+
+        Ok(aggregate_public_key)
+    }
+
+    /// Decodes a concatenated list of compressed public keys, in parallel.
+    ///
+    /// The order of the keys is preserved. If multiple keys are invalid, the error for the key
+    /// with the lowest index is returned.
+    #[cfg(feature = "parallel")]
+    pub fn par_decode_list(bytes: &[u8]) -> Result<Vec<Self>, DecodeError> {
+        if bytes.len() % BLS_PUBLIC_KEY_BYTE_SIZE != 0 {
+            return Err(DecodeError::TooShort);
+        }
+
+        let results: Vec<Result<Self, DecodeError>> = bytes
+            .par_chunks(BLS_PUBLIC_KEY_BYTE_SIZE)
+            .map(|chunk| PublicKey::from_bytes(chunk).map_err(|_| point_decode_error(chunk)))
+            .collect();
+
+        // Collecting in series ensures the first error is always the one returned.
+        results.into_iter().collect()
     }
 
     /// Returns the PublicKey as (x, y) bytes
-    pub fn as_uncompressed_bytes(&self) -> Vec<u8> {
-        RawPublicKey::as_uncompressed_bytes(&mut self.0.clone())
+    ///
+    /// The bytes are computed by the first call, later calls return the cached bytes.
+    pub fn as_uncompressed_bytes(&self) -> &[u8] {
+        self.uncompressed
+            .get_or_init(|| self.raw.clone().as_uncompressed_bytes())
     }
 
     /// Converts (x, y) bytes to PublicKey
-    pub fn from_uncompressed_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+    pub fn from_uncompressed_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() != BLS_PUBLIC_KEY_UNCOMPRESSED_BYTE_SIZE {
+            return Err(Error::WrongLength {
+                expected: BLS_PUBLIC_KEY_UNCOMPRESSED_BYTE_SIZE,
+                got: bytes.len(),
+            });
+        }
+
         let pubkey =
-            RawPublicKey::from_uncompressed_bytes(&bytes).map_err(|_| DecodeError::Invalid)?;
-        Ok(PublicKey(pubkey))
+            RawPublicKey::from_uncompressed_bytes(&bytes).map_err(|_| Error::InvalidBytes)?;
+        Ok(PublicKey::from_raw(pubkey))
+    }
+
+    /// Recovers a PublicKey from its big-endian `x` coordinate and the parity of its `y`
+    /// coordinate.
+    ///
+    /// Returns `Error::InvalidBytes` if there is no point with the given `x` coordinate.
+    pub fn from_x_and_parity(x: &[u8], y_is_odd: bool) -> Result<Self, Error> {
+        if x.len() != BLS_PUBLIC_KEY_BYTE_SIZE {
+            return Err(Error::WrongLength {
+                expected: BLS_PUBLIC_KEY_BYTE_SIZE,
+                got: x.len(),
+            });
+        }
+        // The three most significant bits are reserved for flags in the compressed form.
+        if x[0] & 0xe0 != 0 {
+            return Err(Error::InvalidBytes);
+        }
+
+        // The compressed form distinguishes the two `y` values by their magnitude, not their
+        // parity. Try both and select the point with the required parity.
+        for &sign_flag in &[0x00, 0x20] {
+            let mut bytes = x.to_vec();
+            bytes[0] |= 0x80 | sign_flag;
+
+            let pubkey = PublicKey::from_bytes(&bytes)?;
+            let y_lsb = pubkey.as_uncompressed_bytes().last().cloned().unwrap_or(0);
+
+            if (y_lsb & 1 == 1) == y_is_odd {
+                return Ok(pubkey);
+            }
+        }
+
+        Err(Error::InvalidBytes)
+    }
+
+    /// Converts bytes with ZCash-style flags in the three most significant bits to a PublicKey.
+    ///
+    /// The flags are, from the most significant bit: compression, infinity and sort. A set
+    /// compression flag requires the 48-byte compressed form, otherwise the 96-byte uncompressed
+    /// form is required. Rejects inconsistent flags (e.g., a sort flag on the point at infinity,
+    /// or on an uncompressed point) and infinity points with any other bits set.
+    pub fn from_bytes_with_flags(bytes: &[u8]) -> Result<Self, Error> {
+        let first_byte = bytes.first().cloned().ok_or(Error::WrongLength {
+            expected: BLS_PUBLIC_KEY_BYTE_SIZE,
+            got: 0,
+        })?;
+        let compressed = first_byte & COMPRESSION_FLAG != 0;
+        let infinity = first_byte & INFINITY_FLAG != 0;
+        let sort = first_byte & SORT_FLAG != 0;
+
+        let expected = if compressed {
+            BLS_PUBLIC_KEY_BYTE_SIZE
+        } else {
+            BLS_PUBLIC_KEY_UNCOMPRESSED_BYTE_SIZE
+        };
+        if bytes.len() != expected {
+            return Err(Error::WrongLength {
+                expected,
+                got: bytes.len(),
+            });
+        }
+
+        if infinity {
+            // The point at infinity has no `y` to sort and no `x` coordinate.
+            if sort || first_byte & !FLAG_MASK != 0 || bytes[1..].iter().any(|byte| *byte != 0) {
+                return Err(Error::InvalidBytes);
+            }
+            let mut compressed_bytes = [0; BLS_PUBLIC_KEY_BYTE_SIZE];
+            compressed_bytes[0] = COMPRESSION_FLAG | INFINITY_FLAG;
+            PublicKey::from_bytes(&compressed_bytes)
+        } else if compressed {
+            PublicKey::from_bytes(bytes)
+        } else if sort {
+            Err(Error::InvalidBytes)
+        } else {
+            PublicKey::from_uncompressed_bytes(bytes)
+        }
+    }
+
+    /// Returns the compressed or uncompressed bytes of the PublicKey, with ZCash-style flags.
+    ///
+    /// See `from_bytes_with_flags`.
+    pub fn to_bytes_with_flags(&self, compressed: bool) -> Vec<u8> {
+        if compressed {
+            // The compressed form of the raw library already uses the ZCash-style flags.
+            self.as_bytes().to_vec()
+        } else if self.is_infinity() {
+            let mut bytes = vec![0; BLS_PUBLIC_KEY_UNCOMPRESSED_BYTE_SIZE];
+            bytes[0] = INFINITY_FLAG;
+            bytes
+        } else {
+            self.as_uncompressed_bytes().to_vec()
+        }
+    }
+
+    /// Decodes `signature_bytes` and verifies the signature over `message` and `domain`.
+    ///
+    /// Distinguishes a malformed signature (an `Err`) from a well-formed signature which does not
+    /// verify (`Ok(false)`), e.g., for rejecting garbage signatures received from the network. The
+    /// all-zero (empty) signature is well-formed, and never verifies.
+    pub fn try_verify(
+        &self,
+        message: &[u8],
+        domain: u64,
+        signature_bytes: &[u8],
+    ) -> Result<bool, Error> {
+        if signature_bytes.len() != BLS_SIG_BYTE_SIZE {
+            return Err(Error::WrongLength {
+                expected: BLS_SIG_BYTE_SIZE,
+                got: signature_bytes.len(),
+            });
+        }
+
+        let signature = decode::<Signature>(signature_bytes).map_err(|_| Error::InvalidBytes)?;
+
+        Ok(signature.verify(message, domain, self))
+    }
+
+    /// Verifies `signature` over `message` and `domain`, returning the reason for any failure.
+    ///
+    /// Intended for diagnosing signature failures (e.g., a domain mismatch) in tests and during
+    /// development, consensus code should use `Signature::verify`.
+    pub fn verify_with_reason(
+        &self,
+        message: &[u8],
+        domain: u64,
+        signature: &Signature,
+    ) -> Result<(), VerifyError> {
+        if signature.is_empty() {
+            Err(VerifyError::EmptySignature)
+        } else if self.is_infinity() {
+            Err(VerifyError::InfinityPublicKey)
+        } else if !signature.verify(message, domain, self) {
+            Err(VerifyError::PairingCheckFailed)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Decompresses the compressed bytes of `self` and checks that they decode to the stored point.
+    ///
+    /// A diagnostic for encoding issues (e.g., a raw library which does not round-trip its own
+    /// encoding). Returns `Error::EncodingMismatch` if the points differ.
+    pub fn validate_encoding(&self) -> Result<(), Error> {
+        let mut decoded = raw_from_bytes(self.as_bytes())?;
+
+        let mut stored = self.raw.clone();
+        if decoded.as_uncompressed_bytes() == self.as_uncompressed_bytes()
+            && stored.as_uncompressed_bytes() == self.as_uncompressed_bytes()
+            && decoded.as_bytes() == &self.compressed[..]
+        {
+            Ok(())
+        } else {
+            Err(Error::EncodingMismatch)
+        }
+    }
+
+    /// Returns `true` if `self` is the point at infinity.
+    pub fn is_infinity(&self) -> bool {
+        let bytes = self.as_bytes();
+        // The compressed point at infinity has only the compression and infinity flags set.
+        bytes[0] == 0xc0 && bytes[1..].iter().all(|byte| *byte == 0)
     }
 
     /// Returns the last 6 bytes of the SSZ encoding of the public key, as a hex string.
@@ -51,6 +346,106 @@ impl PublicKey {
         let end_bytes = &bytes[bytes.len().saturating_sub(6)..bytes.len()];
         hex_encode(end_bytes)
     }
+
+    /// Returns the first and last 4 bytes of the SSZ encoding of the public key, as a
+    /// `0x`-prefixed hex string (e.g., `0xaabbccdd…11223344`).
+    ///
+    /// Useful for deposit tooling, where a short identifier must be matched by eye.
+    pub fn validator_id(&self) -> String {
+        let bytes = ssz_encode(self);
+        let start_bytes = &bytes[..4];
+        let end_bytes = &bytes[bytes.len() - 4..];
+        // `hex_encode` is `0x`-prefixed, only the start of the id has the prefix.
+        format!(
+            "{}…{}",
+            hex_encode(start_bytes),
+            &hex_encode(end_bytes)[2..]
+        )
+    }
+
+    /// Returns the 64-bit FNV-1a hash of the compressed bytes of the key.
+    ///
+    /// Unlike `Hash`, the result is stable across versions of this crate (and its dependencies)
+    /// so it is suitable for persisted keying. It is not a cryptographic hash.
+    pub fn stable_hash(&self) -> u64 {
+        let mut hasher = FnvHasher::default();
+        hasher.write(self.as_bytes());
+        hasher.finish()
+    }
+
+    /// Returns the 32-byte commitment to this key, its `tree_hash_root`.
+    ///
+    /// Used wherever a record stores the hash of a key rather than the key itself (e.g.,
+    /// withdrawal credentials).
+    pub fn key_commitment(&self) -> H256 {
+        self.tree_hash_root()
+    }
+}
+
+/// The ZCash-style flags in the most significant byte of a serialized point.
+const COMPRESSION_FLAG: u8 = 0x80;
+const INFINITY_FLAG: u8 = 0x40;
+const SORT_FLAG: u8 = 0x20;
+const FLAG_MASK: u8 = COMPRESSION_FLAG | INFINITY_FLAG | SORT_FLAG;
+
+/// The BLS12-381 field modulus, big-endian.
+const FIELD_MODULUS: [u8; BLS_PUBLIC_KEY_BYTE_SIZE] = [
+    0x1a, 0x01, 0x11, 0xea, 0x39, 0x7f, 0xe6, 0x9a, 0x4b, 0x1b, 0xa7, 0xb6, 0x43, 0x4b, 0xac, 0xd7,
+    0x64, 0x77, 0x4b, 0x84, 0xf3, 0x85, 0x12, 0xbf, 0x67, 0x30, 0xd2, 0xa0, 0xf6, 0xb0, 0xf6, 0x24,
+    0x1e, 0xab, 0xff, 0xfe, 0xb1, 0x53, 0xff, 0xff, 0xb9, 0xfe, 0xff, 0xff, 0xff, 0xff, 0xaa, 0xab,
+];
+
+/// Returns the reason the raw library rejected the compressed point `bytes`.
+///
+/// The raw library does not report why a point was rejected, so the encoding is inspected here.
+/// Any well-formed encoding which was rejected is reported as `NotOnCurve`.
+fn compressed_point_error(bytes: &[u8]) -> BlsPointError {
+    if bytes.len() != BLS_PUBLIC_KEY_BYTE_SIZE {
+        return BlsPointError::MalformedEncoding;
+    }
+
+    let compression_flag = bytes[0] & 0x80 != 0;
+    let infinity_flag = bytes[0] & 0x40 != 0;
+
+    let mut x = bytes.to_vec();
+    x[0] &= 0x1f;
+
+    if !compression_flag {
+        BlsPointError::MalformedEncoding
+    } else if infinity_flag {
+        // The point at infinity must have all other bits (including the sign flag) unset.
+        BlsPointError::MalformedEncoding
+    } else if x[..] >= FIELD_MODULUS[..] {
+        BlsPointError::MalformedEncoding
+    } else {
+        BlsPointError::NotOnCurve
+    }
+}
+
+/// Decodes compressed `bytes` to a raw public key, without computing the cached bytes of a
+/// `PublicKey`.
+pub(crate) fn raw_from_bytes(bytes: &[u8]) -> Result<RawPublicKey, Error> {
+    if bytes.len() != BLS_PUBLIC_KEY_BYTE_SIZE {
+        return Err(Error::WrongLength {
+            expected: BLS_PUBLIC_KEY_BYTE_SIZE,
+            got: bytes.len(),
+        });
+    }
+
+    RawPublicKey::from_bytes(&bytes).map_err(|_| Error::InvalidBytes)
+}
+
+/// Returns the `DecodeError` for compressed point `bytes` which were rejected by `from_bytes`.
+///
+/// Used at the SSZ boundary, where the reason is reported as a `BlsPointError`.
+fn point_decode_error(bytes: &[u8]) -> DecodeError {
+    DecodeError::BlsInvalidPoint(compressed_point_error(bytes))
+}
+
+impl From<RawPublicKey> for PublicKey {
+    fn from(raw: RawPublicKey) -> Self {
+        PublicKey::from_raw(raw)
+    }
 }
 
 impl fmt::Display for PublicKey {
@@ -68,36 +463,49 @@ impl default::Default for PublicKey {
 
 impl Encodable for PublicKey {
     fn ssz_append(&self, s: &mut SszStream) {
-        s.append_encoded_raw(&self.0.as_bytes());
+        // Written directly from the cached bytes, avoiding an allocation per key.
+        s.append_fixed_bytes(self.as_bytes());
     }
 }
 
 impl Decodable for PublicKey {
     fn ssz_decode(bytes: &[u8], i: usize) -> Result<(Self, usize), DecodeError> {
-        if bytes.len() - i < BLS_PUBLIC_KEY_BYTE_SIZE {
+        if bytes.len() - i < PublicKey::SSZ_BYTES {
             return Err(DecodeError::TooShort);
         }
-        let raw_sig = RawPublicKey::from_bytes(&bytes[i..(i + BLS_PUBLIC_KEY_BYTE_SIZE)])
-            .map_err(|_| DecodeError::TooShort)?;
-        Ok((PublicKey(raw_sig), i + BLS_PUBLIC_KEY_BYTE_SIZE))
+        let bytes = &bytes[i..(i + PublicKey::SSZ_BYTES)];
+        let pubkey = PublicKey::from_bytes(bytes).map_err(|_| point_decode_error(bytes))?;
+        Ok((pubkey, i + PublicKey::SSZ_BYTES))
     }
 }
 
 impl Serialize for PublicKey {
+    /// Human-readable formats (e.g., JSON, YAML) use a hex string, all others use the raw bytes.
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        serializer.serialize_str(&hex_encode(self.as_raw().as_bytes()))
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&hex_encode(self.as_bytes()))
+        } else {
+            serializer.serialize_bytes(self.as_bytes())
+        }
     }
 }
 
 impl<'de> Deserialize<'de> for PublicKey {
+    /// Human-readable formats (e.g., JSON, YAML) use a hex string, all others use the raw bytes.
+    ///
+    /// Human-readable formats also accept an array of 48 byte values, as emitted by some tools.
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: Deserializer<'de>,
     {
-        let bytes = deserializer.deserialize_str(HexVisitor)?;
+        let bytes = if deserializer.is_human_readable() {
+            deserializer.deserialize_any(HexOrSeqVisitor)?
+        } else {
+            deserializer.deserialize_bytes(BytesVisitor)?
+        };
         let pubkey = decode(&bytes[..])
             .map_err(|e| serde::de::Error::custom(format!("invalid pubkey ({:?})", e)))?;
         Ok(pubkey)
@@ -106,30 +514,40 @@ impl<'de> Deserialize<'de> for PublicKey {
 
 impl TreeHash for PublicKey {
     fn hash_tree_root(&self) -> Vec<u8> {
-        hash(&self.0.as_bytes())
+        hash(self.as_bytes())
+    }
+
+    fn tree_hash_root(&self) -> H256 {
+        H256::from(hash_fixed(self.as_bytes()))
     }
 }
 
 impl PartialEq for PublicKey {
+    /// Keys are equal if they are the same point, regardless of whether they were decoded from
+    /// compressed or uncompressed bytes. Both forms are always derived from the raw point (see
+    /// `from_raw`), so the compressed bytes of a point are unique.
     fn eq(&self, other: &PublicKey) -> bool {
-        ssz_encode(self) == ssz_encode(other)
+        self.as_bytes() == other.as_bytes()
     }
 }
 
+impl Eq for PublicKey {}
+
 impl Hash for PublicKey {
     /// Note: this is distinct from consensus serialization, it will produce a different hash.
     ///
-    /// This method uses the uncompressed bytes, which are much faster to obtain than the
-    /// compressed bytes required for consensus serialization.
+    /// This method uses the (cached) compressed bytes, the same bytes compared by `PartialEq`, so
+    /// that equal keys always have equal hashes.
     ///
     /// Use `ssz::Encode` to obtain the bytes required for consensus hashing.
     fn hash<H: Hasher>(&self, state: &mut H) {
-        self.as_uncompressed_bytes().hash(state)
+        self.as_bytes().hash(state)
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use super::super::{AggregateSignature, Keypair, BLS_SECRET_KEY_BYTE_SIZE};
     use super::*;
     use ssz::ssz_encode;
 
@@ -143,4 +561,623 @@ mod tests {
 
         assert_eq!(original, decoded);
     }
+
+    #[test]
+    pub fn test_combine() {
+        let keypairs: Vec<Keypair> = (0..4).map(|_| Keypair::random()).collect();
+        let pubkeys: Vec<&PublicKey> = keypairs.iter().map(|kp| &kp.pk).collect();
+        let message = vec![42; 32];
+
+        let combined = PublicKey::combine(&pubkeys);
+
+        let mut signature = AggregateSignature::new();
+        for keypair in &keypairs {
+            signature.add(&Signature::new(&message, 1, &keypair.sk));
+        }
+
+        let mut aggregate_public_key = AggregatePublicKey::new();
+        aggregate_public_key.add(&combined);
+
+        assert!(signature.verify(&message, 1, &aggregate_public_key));
+        assert_eq!(PublicKey::combine(&pubkeys[0..1]), keypairs[0].pk);
+
+        // The compressed point at infinity.
+        let mut infinity = vec![0; BLS_PUBLIC_KEY_BYTE_SIZE];
+        infinity[0] = 0xc0;
+        assert_eq!(ssz_encode(&PublicKey::combine(&[])), infinity);
+        assert!(PublicKey::combine(&[]).is_infinity());
+        assert!(!combined.is_infinity());
+    }
+
+    #[test]
+    pub fn test_verify_with_reason() {
+        let keypair = Keypair::random();
+        let message = vec![42; 32];
+        let signature = Signature::new(&message, 1, &keypair.sk);
+
+        assert_eq!(
+            keypair.pk.verify_with_reason(&message, 1, &signature),
+            Ok(())
+        );
+        assert_eq!(
+            PublicKey::combine(&[]).verify_with_reason(&message, 1, &signature),
+            Err(VerifyError::InfinityPublicKey)
+        );
+    }
+
+    #[test]
+    #[cfg(not(debug_assertions))]
+    pub fn test_verify_with_reason_failures() {
+        let keypair = Keypair::random();
+        let message = vec![42; 32];
+        let signature = Signature::new(&message, 1, &keypair.sk);
+
+        assert_eq!(
+            keypair
+                .pk
+                .verify_with_reason(&message, 1, &Signature::empty_signature()),
+            Err(VerifyError::EmptySignature)
+        );
+        // Signed under domain 1, verified under domain 2.
+        assert_eq!(
+            keypair.pk.verify_with_reason(&message, 2, &signature),
+            Err(VerifyError::PairingCheckFailed)
+        );
+    }
+
+    #[test]
+    pub fn test_ssz_append_unchanged() {
+        let pubkeys: Vec<PublicKey> = (0..4)
+            .map(|_| PublicKey::from_secret_key(&SecretKey::random()))
+            .collect();
+
+        for pubkey in &pubkeys {
+            assert_eq!(ssz_encode(pubkey), pubkey.as_raw().as_bytes());
+            assert_eq!(pubkey.as_bytes(), &pubkey.as_raw().as_bytes()[..]);
+        }
+
+        let mut expected = vec![];
+        for pubkey in &pubkeys {
+            expected.append(&mut pubkey.as_raw().as_bytes());
+        }
+        let mut stream = SszStream::new();
+        for pubkey in &pubkeys {
+            stream.append(pubkey);
+        }
+        assert_eq!(stream.drain(), expected);
+    }
+
+    #[test]
+    pub fn test_uncompressed_bytes() {
+        let sk = SecretKey::random();
+        let pk = PublicKey::from_secret_key(&sk);
+
+        // The bytes are only computed when first read.
+        assert!(pk.uncompressed.get().is_none());
+        let expected = pk.as_raw().clone().as_uncompressed_bytes();
+        assert_eq!(pk.as_uncompressed_bytes(), &expected[..]);
+        assert!(pk.uncompressed.get().is_some());
+        assert_eq!(pk.as_uncompressed_bytes(), &expected[..]);
+
+        let decoded = PublicKey::from_uncompressed_bytes(&expected).unwrap();
+        assert_eq!(decoded.as_uncompressed_bytes(), &expected[..]);
+        assert_eq!(decoded, pk);
+
+        let (decoded, _) = PublicKey::ssz_decode(&ssz_encode(&pk), 0).unwrap();
+        assert_eq!(decoded.as_uncompressed_bytes(), &expected[..]);
+    }
+
+    #[test]
+    pub fn test_try_verify() {
+        let keypair = Keypair::random();
+        let message = [42; 32];
+        let signature_bytes = ssz_encode(&keypair.sign(&message, 1));
+
+        assert_eq!(
+            keypair.pk.try_verify(&message, 1, &signature_bytes),
+            Ok(true)
+        );
+        assert_eq!(
+            keypair.pk.try_verify(&message, 1, &signature_bytes[1..]),
+            Err(Error::WrongLength {
+                expected: BLS_SIG_BYTE_SIZE,
+                got: BLS_SIG_BYTE_SIZE - 1
+            })
+        );
+    }
+
+    #[test]
+    #[cfg(not(debug_assertions))]
+    pub fn test_try_verify_failures() {
+        let keypair = Keypair::random();
+        let message = [42; 32];
+        let signature_bytes = ssz_encode(&keypair.sign(&message, 1));
+
+        // Well-formed signatures which do not verify.
+        assert_eq!(
+            keypair.pk.try_verify(&message, 2, &signature_bytes),
+            Ok(false)
+        );
+        assert_eq!(
+            Keypair::random()
+                .pk
+                .try_verify(&message, 1, &signature_bytes),
+            Ok(false)
+        );
+        assert_eq!(
+            keypair.pk.try_verify(&message, 1, &[0; BLS_SIG_BYTE_SIZE]),
+            Ok(false)
+        );
+
+        // Not a compressed point, the compression flag is unset.
+        assert_eq!(
+            keypair
+                .pk
+                .try_verify(&message, 1, &[0x01; BLS_SIG_BYTE_SIZE]),
+            Err(Error::InvalidBytes)
+        );
+    }
+
+    #[test]
+    pub fn test_ssz_snappy_round_trip() {
+        let pk = PublicKey::from_secret_key(&SecretKey::random());
+
+        let bytes = ssz::ssz_snappy_encode(&pk);
+        assert_eq!(ssz::ssz_snappy_decode::<PublicKey>(&bytes), Ok(pk));
+        assert_eq!(
+            ssz::ssz_snappy_decode::<PublicKey>(&ssz_encode(&pk)),
+            Err(DecodeError::SnappyDecompression)
+        );
+    }
+
+    #[test]
+    pub fn test_ssz_bytes() {
+        let pk = PublicKey::from_secret_key(&SecretKey::random());
+
+        assert_eq!(PublicKey::SSZ_BYTES, BLS_PUBLIC_KEY_BYTE_SIZE);
+        assert_eq!(ssz_encode(&pk).len(), PublicKey::SSZ_BYTES);
+    }
+
+    #[test]
+    pub fn test_deserialize_hex_or_array() {
+        let pk = PublicKey::from_secret_key(&SecretKey::random());
+
+        let hex_json = format!("\"{}\"", hex_encode(pk.as_bytes()));
+        let array_json = serde_json::to_string(&pk.as_bytes().to_vec()).unwrap();
+        assert!(array_json.starts_with('['));
+
+        assert_eq!(serde_json::from_str::<PublicKey>(&hex_json).unwrap(), pk);
+        assert_eq!(serde_json::from_str::<PublicKey>(&array_json).unwrap(), pk);
+
+        // An array must still be a valid key.
+        let short_json = serde_json::to_string(&pk.as_bytes()[1..].to_vec()).unwrap();
+        assert!(serde_json::from_str::<PublicKey>(&short_json).is_err());
+        assert!(serde_json::from_str::<PublicKey>("[256]").is_err());
+    }
+
+    #[test]
+    pub fn test_from_raw() {
+        let pk = PublicKey::from_secret_key(&SecretKey::random());
+
+        assert_eq!(PublicKey::from_raw(pk.as_raw().clone()), pk);
+        assert_eq!(PublicKey::from(pk.as_raw().clone()), pk);
+        assert_eq!(
+            PublicKey::from_raw(pk.as_raw().clone()).as_uncompressed_bytes(),
+            pk.as_uncompressed_bytes()
+        );
+    }
+
+    #[test]
+    pub fn test_compressed_and_uncompressed_are_equal() {
+        let pk = PublicKey::from_secret_key(&SecretKey::random());
+
+        let from_compressed = PublicKey::from_bytes(pk.as_bytes()).unwrap();
+        let from_uncompressed =
+            PublicKey::from_uncompressed_bytes(pk.as_uncompressed_bytes()).unwrap();
+
+        assert_eq!(from_compressed, from_uncompressed);
+        assert_eq!(from_compressed.as_bytes(), from_uncompressed.as_bytes());
+        assert_eq!(
+            from_compressed.as_uncompressed_bytes(),
+            from_uncompressed.as_uncompressed_bytes()
+        );
+
+        let mut set = std::collections::HashSet::new();
+        set.insert(from_compressed);
+        assert!(set.contains(&from_uncompressed));
+    }
+
+    #[test]
+    pub fn test_from_x_and_parity() {
+        let pk = PublicKey::from_secret_key(&SecretKey::random());
+
+        let mut x = pk.as_raw().as_bytes();
+        x[0] &= 0x1f;
+        let y_is_odd = pk.as_uncompressed_bytes().last().unwrap() & 1 == 1;
+
+        assert_eq!(PublicKey::from_x_and_parity(&x, y_is_odd), Ok(pk.clone()));
+
+        // The other `y` is the negation of the key.
+        let negated = PublicKey::from_x_and_parity(&x, !y_is_odd).unwrap();
+        assert_ne!(negated, pk);
+        let mut negated_x = negated.as_raw().as_bytes();
+        negated_x[0] &= 0x1f;
+        assert_eq!(negated_x, x);
+    }
+
+    #[test]
+    pub fn test_from_x_and_parity_without_y() {
+        // `1^3 + 4` is not a square modulo the field prime, so there is no point with `x = 1`.
+        let mut x = vec![0; BLS_PUBLIC_KEY_BYTE_SIZE];
+        x[BLS_PUBLIC_KEY_BYTE_SIZE - 1] = 1;
+
+        assert_eq!(
+            PublicKey::from_x_and_parity(&x, true),
+            Err(Error::InvalidBytes)
+        );
+        assert_eq!(
+            PublicKey::from_x_and_parity(&x, false),
+            Err(Error::InvalidBytes)
+        );
+        assert_eq!(
+            PublicKey::from_x_and_parity(&x[1..], false),
+            Err(Error::WrongLength {
+                expected: BLS_PUBLIC_KEY_BYTE_SIZE,
+                got: BLS_PUBLIC_KEY_BYTE_SIZE - 1,
+            })
+        );
+    }
+
+    #[test]
+    pub fn test_validate_encoding() {
+        let sk = SecretKey::random();
+        let pk = PublicKey::from_secret_key(&sk);
+
+        assert_eq!(pk.validate_encoding(), Ok(()));
+
+        let decoded = PublicKey::from_bytes(pk.as_bytes()).unwrap();
+        assert_eq!(decoded.validate_encoding(), Ok(()));
+    }
+
+    #[test]
+    pub fn test_tree_hash_root() {
+        let pk = PublicKey::from_secret_key(&SecretKey::random());
+
+        assert_eq!(pk.tree_hash_root().as_bytes(), &pk.hash_tree_root()[..]);
+    }
+
+    #[test]
+    pub fn test_try_aggregate_from_ssz() {
+        let keypairs: Vec<Keypair> = (0..5).map(|_| Keypair::random()).collect();
+        let message = vec![42; 32];
+
+        let mut bytes = vec![];
+        let mut signature = AggregateSignature::new();
+        for keypair in &keypairs {
+            bytes.append(&mut ssz_encode(&keypair.pk));
+            signature.add(&Signature::new(&message, 1, &keypair.sk));
+        }
+
+        let aggregate_public_key = PublicKey::try_aggregate_from_ssz(&bytes).unwrap();
+        assert!(aggregate_public_key.verify(&message, 1, &signature));
+
+        assert_eq!(
+            PublicKey::try_aggregate_from_ssz(&bytes[..bytes.len() - 1]).err(),
+            Some((4, DecodeError::TooShort))
+        );
+    }
+
+    fn point_error(bytes: &[u8]) -> Option<BlsPointError> {
+        assert_eq!(PublicKey::from_bytes(bytes), Err(Error::InvalidBytes));
+
+        match decode::<PublicKey>(bytes) {
+            Err(DecodeError::BlsInvalidPoint(reason)) => Some(reason),
+            _ => None,
+        }
+    }
+
+    #[test]
+    pub fn test_from_bytes_point_errors() {
+        let valid = PublicKey::from_secret_key(&SecretKey::random())
+            .as_bytes()
+            .to_vec();
+
+        // Missing the compression flag.
+        let mut bytes = valid.clone();
+        bytes[0] &= 0x7f;
+        assert_eq!(point_error(&bytes), Some(BlsPointError::MalformedEncoding));
+
+        // The point at infinity with a non-zero coordinate.
+        let mut bytes = vec![0; BLS_PUBLIC_KEY_BYTE_SIZE];
+        bytes[0] = 0xc0;
+        bytes[BLS_PUBLIC_KEY_BYTE_SIZE - 1] = 1;
+        assert_eq!(point_error(&bytes), Some(BlsPointError::MalformedEncoding));
+
+        // An `x` coordinate which is not less than the field modulus.
+        let mut bytes = FIELD_MODULUS.to_vec();
+        bytes[0] |= 0x80;
+        assert_eq!(point_error(&bytes), Some(BlsPointError::MalformedEncoding));
+
+        // A well-formed `x` coordinate with no corresponding `y`.
+        let mut bytes = vec![0; BLS_PUBLIC_KEY_BYTE_SIZE];
+        bytes[0] = 0x80;
+        bytes[BLS_PUBLIC_KEY_BYTE_SIZE - 1] = 1;
+        assert_eq!(point_error(&bytes), Some(BlsPointError::NotOnCurve));
+    }
+
+    #[test]
+    pub fn test_from_bytes_errors() {
+        let pk = PublicKey::from_secret_key(&SecretKey::random());
+
+        assert_eq!(
+            PublicKey::from_bytes(&pk.as_bytes()[1..]),
+            Err(Error::WrongLength {
+                expected: BLS_PUBLIC_KEY_BYTE_SIZE,
+                got: BLS_PUBLIC_KEY_BYTE_SIZE - 1,
+            })
+        );
+        assert_eq!(
+            PublicKey::from_uncompressed_bytes(pk.as_bytes()),
+            Err(Error::WrongLength {
+                expected: BLS_PUBLIC_KEY_UNCOMPRESSED_BYTE_SIZE,
+                got: BLS_PUBLIC_KEY_BYTE_SIZE,
+            })
+        );
+
+        let mut bytes = pk.as_bytes().to_vec();
+        bytes[0] &= 0x7f;
+        assert_eq!(PublicKey::from_bytes(&bytes), Err(Error::InvalidBytes));
+
+        // The point at infinity is only rejected where required.
+        let mut infinity = vec![0; BLS_PUBLIC_KEY_BYTE_SIZE];
+        infinity[0] = 0xc0;
+        assert!(PublicKey::from_bytes(&infinity).unwrap().is_infinity());
+        assert_eq!(
+            PublicKey::from_bytes_non_infinity(&infinity),
+            Err(Error::InfinityPoint)
+        );
+        assert_eq!(PublicKey::from_bytes_non_infinity(pk.as_bytes()), Ok(pk));
+    }
+
+    #[test]
+    pub fn test_from_uncompressed_bytes_point_errors() {
+        let pk = PublicKey::from_secret_key(&SecretKey::random());
+
+        // Changing `y` moves the point off the curve.
+        let mut bytes = pk.as_uncompressed_bytes().to_vec();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 1;
+
+        assert_eq!(
+            PublicKey::from_uncompressed_bytes(&bytes),
+            Err(Error::InvalidBytes)
+        );
+    }
+
+    #[test]
+    pub fn test_try_aggregate_from_ssz_corrupt_key() {
+        let mut bytes = vec![];
+        for _ in 0..5 {
+            bytes.append(&mut ssz_encode(&PublicKey::from_secret_key(
+                &SecretKey::random(),
+            )));
+        }
+
+        // Corrupt the middle key.
+        bytes[2 * BLS_PUBLIC_KEY_BYTE_SIZE..3 * BLS_PUBLIC_KEY_BYTE_SIZE]
+            .copy_from_slice(&[0xff; BLS_PUBLIC_KEY_BYTE_SIZE]);
+
+        assert_eq!(
+            PublicKey::try_aggregate_from_ssz(&bytes).err(),
+            Some((
+                2,
+                DecodeError::BlsInvalidPoint(BlsPointError::MalformedEncoding)
+            ))
+        );
+    }
+
+    #[test]
+    pub fn test_ssz_encoding_is_compressed_bytes() {
+        let pk = PublicKey::from_secret_key(&SecretKey::random());
+
+        assert_eq!(ssz_encode(&pk), pk.as_raw().as_bytes());
+    }
+
+    #[test]
+    pub fn test_key_commitment() {
+        let pk = PublicKey::from_secret_key(&SecretKey::random());
+
+        assert_eq!(pk.key_commitment().as_bytes(), &pk.hash_tree_root()[..]);
+        assert_eq!(pk.key_commitment().as_bytes(), &hash(&ssz_encode(&pk))[..]);
+    }
+
+    #[test]
+    pub fn test_validator_id() {
+        // The compressed BLS12-381 G1 generator.
+        let bytes = hex::decode(
+            "97f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb",
+        )
+        .unwrap();
+        let pubkey = PublicKey::from_bytes(&bytes).unwrap();
+
+        assert_eq!(pubkey.validator_id(), "0x97f1d3a7…db22c6bb");
+    }
+
+    #[test]
+    pub fn test_bytes_with_flags() {
+        // The BLS12-381 G1 generator, i.e., the public key of the secret key `1`.
+        let compressed = hex::decode(
+            "97f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb",
+        )
+        .unwrap();
+        let uncompressed = hex::decode(
+            "17f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb\
+             08b3f481e3aaa0f1a09e30ed741d8ae4fcf5e095d5d00af600db18cb2c04b3edd03cc744a2888ae40caa232946c5e7e1",
+        )
+        .unwrap();
+
+        let mut sk_bytes = vec![0; BLS_SECRET_KEY_BYTE_SIZE];
+        sk_bytes[BLS_SECRET_KEY_BYTE_SIZE - 1] = 1;
+        let pk = PublicKey::from_secret_key(&SecretKey::from_bytes(&sk_bytes).unwrap());
+
+        assert_eq!(pk.to_bytes_with_flags(true), compressed);
+        assert_eq!(pk.to_bytes_with_flags(false), uncompressed);
+        assert_eq!(
+            PublicKey::from_bytes_with_flags(&compressed),
+            Ok(pk.clone())
+        );
+        assert_eq!(PublicKey::from_bytes_with_flags(&uncompressed), Ok(pk));
+    }
+
+    #[test]
+    pub fn test_stable_hash() {
+        // The compressed BLS12-381 G1 generator.
+        let bytes = hex::decode(
+            "97f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb",
+        )
+        .unwrap();
+        let pubkey = PublicKey::from_bytes(&bytes).unwrap();
+
+        assert_eq!(pubkey.stable_hash(), 0x40ec_0d39_9347_7bd5);
+    }
+
+    #[test]
+    pub fn test_bytes_with_flags_infinity() {
+        let mut compressed = vec![0; BLS_PUBLIC_KEY_BYTE_SIZE];
+        compressed[0] = 0xc0;
+        let mut uncompressed = vec![0; BLS_PUBLIC_KEY_UNCOMPRESSED_BYTE_SIZE];
+        uncompressed[0] = 0x40;
+
+        let infinity = PublicKey::combine(&[]);
+        assert!(infinity.is_infinity());
+
+        assert_eq!(infinity.to_bytes_with_flags(true), compressed);
+        assert_eq!(infinity.to_bytes_with_flags(false), uncompressed);
+        assert_eq!(
+            PublicKey::from_bytes_with_flags(&compressed),
+            Ok(infinity.clone())
+        );
+        assert_eq!(
+            PublicKey::from_bytes_with_flags(&uncompressed),
+            Ok(infinity)
+        );
+    }
+
+    #[test]
+    pub fn test_bytes_with_flags_inconsistent() {
+        let pk = PublicKey::from_secret_key(&SecretKey::random());
+
+        // Sort flag on the point at infinity.
+        let mut bytes = vec![0; BLS_PUBLIC_KEY_BYTE_SIZE];
+        bytes[0] = 0xe0;
+        assert_eq!(
+            PublicKey::from_bytes_with_flags(&bytes),
+            Err(Error::InvalidBytes)
+        );
+
+        // The point at infinity with a non-zero `x` coordinate.
+        let mut bytes = vec![0; BLS_PUBLIC_KEY_BYTE_SIZE];
+        bytes[0] = 0xc0;
+        bytes[47] = 1;
+        assert_eq!(
+            PublicKey::from_bytes_with_flags(&bytes),
+            Err(Error::InvalidBytes)
+        );
+
+        // Sort flag on an uncompressed point.
+        let mut bytes = pk.to_bytes_with_flags(false);
+        bytes[0] |= 0x20;
+        assert_eq!(
+            PublicKey::from_bytes_with_flags(&bytes),
+            Err(Error::InvalidBytes)
+        );
+
+        // Compression flag on the uncompressed form, and vice versa.
+        let mut bytes = pk.to_bytes_with_flags(false);
+        bytes[0] |= 0x80;
+        assert_eq!(
+            PublicKey::from_bytes_with_flags(&bytes),
+            Err(Error::WrongLength {
+                expected: BLS_PUBLIC_KEY_BYTE_SIZE,
+                got: BLS_PUBLIC_KEY_UNCOMPRESSED_BYTE_SIZE
+            })
+        );
+        let mut bytes = pk.to_bytes_with_flags(true);
+        bytes[0] &= 0x7f;
+        assert_eq!(
+            PublicKey::from_bytes_with_flags(&bytes),
+            Err(Error::WrongLength {
+                expected: BLS_PUBLIC_KEY_UNCOMPRESSED_BYTE_SIZE,
+                got: BLS_PUBLIC_KEY_BYTE_SIZE
+            })
+        );
+        assert_eq!(
+            PublicKey::from_bytes_with_flags(&[]),
+            Err(Error::WrongLength {
+                expected: BLS_PUBLIC_KEY_BYTE_SIZE,
+                got: 0
+            })
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "parallel")]
+    pub fn test_par_decode_list() {
+        let pubkeys: Vec<PublicKey> = (0..256)
+            .map(|_| PublicKey::from_secret_key(&SecretKey::random()))
+            .collect();
+
+        let mut bytes = vec![];
+        for pubkey in &pubkeys {
+            bytes.append(&mut ssz_encode(pubkey));
+        }
+
+        let sequential: Vec<PublicKey> = bytes
+            .chunks(BLS_PUBLIC_KEY_BYTE_SIZE)
+            .map(|chunk| PublicKey::from_bytes(chunk).unwrap())
+            .collect();
+
+        assert_eq!(PublicKey::par_decode_list(&bytes), Ok(sequential));
+        assert_eq!(PublicKey::par_decode_list(&bytes), Ok(pubkeys));
+
+        assert_eq!(
+            PublicKey::par_decode_list(&bytes[1..]),
+            Err(DecodeError::TooShort)
+        );
+
+        // Corrupt a key, it must be reported as invalid.
+        bytes[100 * BLS_PUBLIC_KEY_BYTE_SIZE..101 * BLS_PUBLIC_KEY_BYTE_SIZE]
+            .copy_from_slice(&[0xff; BLS_PUBLIC_KEY_BYTE_SIZE]);
+        assert_eq!(
+            PublicKey::par_decode_list(&bytes),
+            Err(DecodeError::BlsInvalidPoint(
+                BlsPointError::MalformedEncoding
+            ))
+        );
+    }
+
+    #[test]
+    pub fn test_bincode_round_trip() {
+        let original = PublicKey::from_secret_key(&SecretKey::random());
+
+        let bytes = bincode::serialize(&original).unwrap();
+        let decoded: PublicKey = bincode::deserialize(&bytes).unwrap();
+
+        assert_eq!(original, decoded);
+    }
+
+    #[test]
+    pub fn test_json_hex_round_trip() {
+        let original = PublicKey::from_secret_key(&SecretKey::random());
+
+        let json = serde_json::to_string(&original).unwrap();
+        assert_eq!(
+            json,
+            format!("\"{}\"", hex_encode(original.as_raw().as_bytes()))
+        );
+
+        let decoded: PublicKey = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(original, decoded);
+    }
 }
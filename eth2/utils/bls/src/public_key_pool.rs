@@ -0,0 +1,103 @@
+use super::{Error, PublicKey};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Interns `PublicKey`s, so that each distinct key is stored exactly once.
+///
+/// Each key is assigned an index in the order it was first inserted. Keys are shared via `Arc`,
+/// so structures holding many references to the same few keys (e.g., committees) only pay for
+/// each key once.
+#[derive(Debug, Default, Clone)]
+pub struct PublicKeyPool {
+    keys: Vec<Arc<PublicKey>>,
+    indices: HashMap<Arc<PublicKey>, usize>,
+}
+
+impl PublicKeyPool {
+    /// Returns an empty pool.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of distinct keys in the pool.
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Returns `true` if the pool is empty.
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Adds `public_key` to the pool (if it is not already present) and returns its index.
+    pub fn insert(&mut self, public_key: PublicKey) -> usize {
+        if let Some(&index) = self.indices.get(&public_key) {
+            return index;
+        }
+
+        let index = self.keys.len();
+        let public_key = Arc::new(public_key);
+        self.keys.push(public_key.clone());
+        self.indices.insert(public_key, index);
+
+        index
+    }
+
+    /// Adds `public_key` to the pool (if it is not already present) and returns the shared
+    /// instance.
+    pub fn intern(&mut self, public_key: PublicKey) -> Arc<PublicKey> {
+        let index = self.insert(public_key);
+        self.keys[index].clone()
+    }
+
+    /// Decodes a compressed public key from `bytes` and adds it to the pool, returning its index.
+    ///
+    /// See `PublicKey::from_bytes`.
+    pub fn insert_bytes(&mut self, bytes: &[u8]) -> Result<usize, Error> {
+        Ok(self.insert(PublicKey::from_bytes(bytes)?))
+    }
+
+    /// Returns the key with the given `index`, if any.
+    pub fn get(&self, index: usize) -> Option<&Arc<PublicKey>> {
+        self.keys.get(index)
+    }
+
+    /// Returns the index of `public_key`, if it is in the pool.
+    pub fn index_of(&self, public_key: &PublicKey) -> Option<usize> {
+        self.indices.get(public_key).cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::SecretKey;
+    use super::*;
+
+    #[test]
+    pub fn test_duplicates_are_stored_once() {
+        let a = PublicKey::from_secret_key(&SecretKey::random());
+        let b = PublicKey::from_secret_key(&SecretKey::random());
+
+        let mut pool = PublicKeyPool::new();
+
+        assert_eq!(pool.insert(a.clone()), 0);
+        assert_eq!(pool.insert(b.clone()), 1);
+        assert_eq!(pool.insert(a.clone()), 0);
+        assert_eq!(pool.insert_bytes(b.as_bytes()), Ok(1));
+        assert_eq!(pool.len(), 2);
+
+        let first = pool.intern(a.clone());
+        let second = pool.intern(a.clone());
+        assert!(Arc::ptr_eq(&first, &second));
+        assert_eq!(pool.len(), 2);
+
+        assert_eq!(pool.get(0).map(|pk| &**pk), Some(&a));
+        assert_eq!(pool.get(1).map(|pk| &**pk), Some(&b));
+        assert_eq!(pool.get(2), None);
+        assert_eq!(pool.index_of(&b), Some(1));
+        assert_eq!(
+            pool.index_of(&PublicKey::from_secret_key(&SecretKey::random())),
+            None
+        );
+    }
+}
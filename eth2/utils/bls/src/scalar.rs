@@ -0,0 +1,173 @@
+//! Arithmetic on scalars modulo the order of the BLS12-381 curve subgroup.
+//!
+//! A small, simple implementation for operations on secret keys (e.g., `SecretKey::split`). It is
+//! _not_ constant time and must not be used where timing side-channels matter.
+
+use std::cmp::Ordering;
+use zeroize::Zeroize;
+
+/// The order of the BLS12-381 curve subgroup, as little-endian 64-bit limbs.
+const MODULUS: [u64; 4] = [
+    0xffff_ffff_0000_0001,
+    0x53bd_a402_fffe_5bfe,
+    0x3339_d808_09a1_d805,
+    0x73ed_a753_299d_7d48,
+];
+
+/// A scalar in `[0, MODULUS)`, as little-endian 64-bit limbs.
+///
+/// Scalars are derived from secret keys, so the limbs are zeroed when dropped.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Scalar([u64; 4]);
+
+impl Drop for Scalar {
+    fn drop(&mut self) {
+        self.0[..].zeroize();
+    }
+}
+
+impl Scalar {
+    pub fn zero() -> Self {
+        Scalar([0; 4])
+    }
+
+    pub fn from_u64(value: u64) -> Self {
+        Scalar([value, 0, 0, 0])
+    }
+
+    /// Interprets `bytes` as a big-endian integer of any length, reduced modulo `MODULUS`.
+    pub fn from_be_bytes_reduced(bytes: &[u8]) -> Self {
+        bytes.iter().fold(Scalar::zero(), |acc, byte| {
+            let shifted = (0..8).fold(acc, |acc, _| acc.add(&acc));
+            shifted.add(&Scalar::from_u64(u64::from(*byte)))
+        })
+    }
+
+    /// Returns the big-endian bytes of the scalar.
+    pub fn to_be_bytes(&self) -> [u8; 32] {
+        let mut bytes = [0; 32];
+        for (i, limb) in self.0.iter().rev().enumerate() {
+            bytes[i * 8..(i + 1) * 8].copy_from_slice(&limb.to_be_bytes());
+        }
+        bytes
+    }
+
+    pub fn add(&self, other: &Self) -> Self {
+        // Both values are less than `MODULUS < 2^255`, so the sum cannot overflow.
+        let (sum, _) = add_limbs(&self.0, &other.0);
+        if cmp_limbs(&sum, &MODULUS) == Ordering::Less {
+            Scalar(sum)
+        } else {
+            Scalar(sub_limbs(&sum, &MODULUS).0)
+        }
+    }
+
+    pub fn sub(&self, other: &Self) -> Self {
+        let (difference, borrow) = sub_limbs(&self.0, &other.0);
+        if borrow {
+            Scalar(add_limbs(&difference, &MODULUS).0)
+        } else {
+            Scalar(difference)
+        }
+    }
+
+    pub fn mul(&self, other: &Self) -> Self {
+        // Double-and-add, from the most significant bit of `other`.
+        let mut product = Scalar::zero();
+        for limb in other.0.iter().rev() {
+            for bit in (0..64).rev() {
+                product = product.add(&product);
+                if (limb >> bit) & 1 == 1 {
+                    product = product.add(self);
+                }
+            }
+        }
+        product
+    }
+
+    /// Returns the multiplicative inverse, or `None` for zero.
+    pub fn invert(&self) -> Option<Self> {
+        if *self == Scalar::zero() {
+            return None;
+        }
+
+        // Fermat's little theorem, `self^(MODULUS - 2)`.
+        let (exponent, _) = sub_limbs(&MODULUS, &[2, 0, 0, 0]);
+        let mut result = Scalar::from_u64(1);
+        for limb in exponent.iter().rev() {
+            for bit in (0..64).rev() {
+                result = result.mul(&result);
+                if (limb >> bit) & 1 == 1 {
+                    result = result.mul(self);
+                }
+            }
+        }
+        Some(result)
+    }
+}
+
+fn cmp_limbs(a: &[u64; 4], b: &[u64; 4]) -> Ordering {
+    a.iter().rev().cmp(b.iter().rev())
+}
+
+/// Returns `a + b` and whether the addition overflowed.
+fn add_limbs(a: &[u64; 4], b: &[u64; 4]) -> ([u64; 4], bool) {
+    let mut result = [0; 4];
+    let mut carry = false;
+    for (limb, (a, b)) in result.iter_mut().zip(a.iter().zip(b.iter())) {
+        let (sum, overflow_a) = a.overflowing_add(*b);
+        let (sum, overflow_b) = sum.overflowing_add(carry as u64);
+        *limb = sum;
+        carry = overflow_a || overflow_b;
+    }
+    (result, carry)
+}
+
+/// Returns `a - b` and whether the subtraction underflowed.
+fn sub_limbs(a: &[u64; 4], b: &[u64; 4]) -> ([u64; 4], bool) {
+    let mut result = [0; 4];
+    let mut borrow = false;
+    for (limb, (a, b)) in result.iter_mut().zip(a.iter().zip(b.iter())) {
+        let (difference, underflow_a) = a.overflowing_sub(*b);
+        let (difference, underflow_b) = difference.overflowing_sub(borrow as u64);
+        *limb = difference;
+        borrow = underflow_a || underflow_b;
+    }
+    (result, borrow)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn test_reduction() {
+        let mut modulus_bytes = vec![0; 16];
+        modulus_bytes.extend_from_slice(&Scalar(MODULUS).to_be_bytes());
+
+        assert_eq!(
+            Scalar::from_be_bytes_reduced(&modulus_bytes),
+            Scalar::zero()
+        );
+        assert_eq!(
+            Scalar::from_be_bytes_reduced(&[0x01, 0x02]),
+            Scalar::from_u64(0x0102)
+        );
+    }
+
+    #[test]
+    pub fn test_arithmetic() {
+        let a = Scalar::from_be_bytes_reduced(&[0xff; 48]);
+        let b = Scalar::from_u64(42);
+
+        assert_eq!(a.add(&b).sub(&b), a);
+        assert_eq!(b.sub(&a).add(&a), b);
+        assert_eq!(
+            Scalar::zero().sub(&Scalar::from_u64(1)).add(&b),
+            Scalar::from_u64(41)
+        );
+        assert_eq!(b.mul(&Scalar::from_u64(3)), Scalar::from_u64(126));
+        assert_eq!(a.mul(&a.invert().unwrap()), Scalar::from_u64(1));
+        assert_eq!(Scalar::zero().invert(), None);
+    }
+}
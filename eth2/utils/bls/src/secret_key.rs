@@ -1,10 +1,17 @@
-use super::BLS_SECRET_KEY_BYTE_SIZE;
+#[cfg(feature = "threshold-keys")]
+use super::scalar::Scalar;
+use super::{Signature, BLS_SECRET_KEY_BYTE_SIZE};
 use bls_aggregates::{DecodeError as BlsDecodeError, SecretKey as RawSecretKey};
 use hex::encode as hex_encode;
+#[cfg(feature = "threshold-keys")]
+use rand::thread_rng;
+use rand::{CryptoRng, RngCore};
 use serde::de::{Deserialize, Deserializer};
 use serde::ser::{Serialize, Serializer};
 use serde_hex::HexVisitor;
 use ssz::{decode, ssz_encode, Decodable, DecodeError, Encodable, SszStream, TreeHash};
+#[cfg(any(feature = "secret-serialization", feature = "threshold-keys"))]
+use zeroize::{Zeroize, Zeroizing};
 
 /// A single BLS signature.
 ///
@@ -13,11 +20,36 @@ use ssz::{decode, ssz_encode, Decodable, DecodeError, Encodable, SszStream, Tree
 #[derive(Debug, PartialEq, Clone, Eq)]
 pub struct SecretKey(RawSecretKey);
 
+/// The order of the BLS12-381 curve subgroup, big-endian.
+const CURVE_ORDER: [u8; 32] = [
+    0x73, 0xed, 0xa7, 0x53, 0x29, 0x9d, 0x7d, 0x48, 0x33, 0x39, 0xd8, 0x08, 0x09, 0xa1, 0xd8, 0x05,
+    0x53, 0xbd, 0xa4, 0x02, 0xff, 0xfe, 0x5b, 0xfe, 0xff, 0xff, 0xff, 0xff, 0x00, 0x00, 0x00, 0x01,
+];
+
 impl SecretKey {
     pub fn random() -> Self {
         SecretKey(RawSecretKey::random())
     }
 
+    /// Generates a random `SecretKey` using the given `rng`.
+    ///
+    /// A seeded `rng` always generates the same key, which is useful for reproducible tests.
+    pub fn random_with_rng<R: RngCore + CryptoRng>(rng: &mut R) -> Self {
+        // Rejection sampling, so that the key is uniform in `[1, CURVE_ORDER)`.
+        loop {
+            let mut scalar = [0; 32];
+            rng.fill_bytes(&mut scalar);
+
+            if scalar < CURVE_ORDER && scalar != [0; 32] {
+                let mut bytes = vec![0; BLS_SECRET_KEY_BYTE_SIZE - scalar.len()];
+                bytes.extend_from_slice(&scalar);
+
+                return SecretKey::from_bytes(&bytes)
+                    .expect("a key of the correct length is always valid");
+            }
+        }
+    }
+
     /// Instantiate a SecretKey from existing bytes.
     ///
     /// Note: this is _not_ SSZ decoding.
@@ -25,10 +57,138 @@ impl SecretKey {
         Ok(SecretKey(RawSecretKey::from_bytes(bytes)?))
     }
 
+    /// Signs `message` under the given `domain`.
+    ///
+    /// The `message` should be the 32-byte signing root of the object being signed.
+    pub fn sign(&self, message: &[u8], domain: u64) -> Signature {
+        Signature::new(message, domain, self)
+    }
+
     /// Returns the underlying secret key.
     pub fn as_raw(&self) -> &RawSecretKey {
         &self.0
     }
+
+    /// Splits the key into `n` Shamir shares, any `threshold` of which reconstruct the key (see
+    /// `reconstruct`).
+    ///
+    /// Each share is `(index, share)`, with indices `1..=n`.
+    ///
+    /// Do not use in production. Intended only for experimenting with threshold schemes, the
+    /// arithmetic is not constant time and may leak the key through timing side-channels.
+    ///
+    /// The shares reconstruct the key reduced modulo the curve order, which is the key itself for
+    /// any key generated by `random_with_rng`.
+    ///
+    /// # Panics
+    ///
+    /// If `threshold` is zero or greater than `n`.
+    #[cfg(feature = "threshold-keys")]
+    pub fn split(&self, n: usize, threshold: usize) -> Vec<(usize, SecretKey)> {
+        self.split_with_rng(n, threshold, &mut thread_rng())
+    }
+
+    /// Identical to `split`, however the random coefficients are generated using `rng`.
+    #[cfg(feature = "threshold-keys")]
+    pub fn split_with_rng<R: RngCore + CryptoRng>(
+        &self,
+        n: usize,
+        threshold: usize,
+        rng: &mut R,
+    ) -> Vec<(usize, SecretKey)> {
+        assert!(
+            threshold > 0 && threshold <= n,
+            "threshold must be in 1..=n"
+        );
+
+        // The coefficients of a random polynomial of degree `threshold - 1`, where the constant
+        // term is the key. Allocated once, so no copy is left behind by a reallocation.
+        let mut coefficients = Vec::with_capacity(threshold);
+        coefficients.push(self.to_scalar());
+        for _ in 1..threshold {
+            coefficients.push(SecretKey::random_with_rng(rng).to_scalar());
+        }
+
+        (1..=n)
+            .map(|index| {
+                let x = Scalar::from_u64(index as u64);
+                let y = coefficients
+                    .iter()
+                    .rev()
+                    .fold(Scalar::zero(), |acc, c| acc.mul(&x).add(c));
+
+                (index, SecretKey::from_scalar(&y))
+            })
+            .collect()
+    }
+
+    /// Reconstructs a key from `shares` produced by `split`, by Lagrange interpolation.
+    ///
+    /// If fewer than the `threshold` of the split are supplied, the result is a different
+    /// (unrelated) key. Returns `None` if `shares` is empty, or if an index is zero or repeated.
+    ///
+    /// Not constant time, see `split`.
+    #[cfg(feature = "threshold-keys")]
+    pub fn reconstruct(shares: &[(usize, SecretKey)]) -> Option<SecretKey> {
+        if shares.is_empty() || shares.iter().any(|(index, _)| *index == 0) {
+            return None;
+        }
+
+        let mut secret = Scalar::zero();
+        for (j, (index_j, share)) in shares.iter().enumerate() {
+            let x_j = Scalar::from_u64(*index_j as u64);
+
+            // The Lagrange basis polynomial of share `j`, evaluated at zero. A repeated index
+            // makes the denominator zero.
+            let mut numerator = Scalar::from_u64(1);
+            let mut denominator = Scalar::from_u64(1);
+            for (m, (index_m, _)) in shares.iter().enumerate() {
+                if m != j {
+                    let x_m = Scalar::from_u64(*index_m as u64);
+                    numerator = numerator.mul(&x_m);
+                    denominator = denominator.mul(&x_m.sub(&x_j));
+                }
+            }
+
+            let basis = numerator.mul(&denominator.invert()?);
+            secret = secret.add(&share.to_scalar().mul(&basis));
+        }
+
+        Some(SecretKey::from_scalar(&secret))
+    }
+
+    #[cfg(feature = "threshold-keys")]
+    fn to_scalar(&self) -> Scalar {
+        let mut bytes = self.0.as_bytes();
+        let scalar = Scalar::from_be_bytes_reduced(&bytes);
+        bytes.zeroize();
+
+        scalar
+    }
+
+    #[cfg(feature = "threshold-keys")]
+    fn from_scalar(scalar: &Scalar) -> Self {
+        let scalar_bytes = Zeroizing::new(scalar.to_be_bytes());
+        let mut bytes = Zeroizing::new([0; BLS_SECRET_KEY_BYTE_SIZE]);
+        bytes[BLS_SECRET_KEY_BYTE_SIZE - scalar_bytes.len()..].copy_from_slice(&scalar_bytes[..]);
+
+        SecretKey::from_bytes(&bytes[..]).expect("a key of the correct length is always valid")
+    }
+
+    /// Returns the bytes of the key, as accepted by `from_bytes`.
+    ///
+    /// The returned buffer is zeroed when dropped. Intended for exporting a key (e.g., to an
+    /// encrypted keystore), the bytes should not be copied out of the buffer.
+    #[cfg(feature = "secret-serialization")]
+    pub fn to_bytes(&self) -> Zeroizing<[u8; BLS_SECRET_KEY_BYTE_SIZE]> {
+        let mut raw_bytes = self.0.as_bytes();
+
+        let mut bytes = Zeroizing::new([0; BLS_SECRET_KEY_BYTE_SIZE]);
+        bytes.copy_from_slice(&raw_bytes);
+        raw_bytes.zeroize();
+
+        bytes
+    }
 }
 
 impl Encodable for SecretKey {
@@ -77,6 +237,7 @@ impl TreeHash for SecretKey {
 
 #[cfg(test)]
 mod tests {
+    use super::super::Keypair;
     use super::*;
     use ssz::ssz_encode;
 
@@ -91,4 +252,86 @@ mod tests {
 
         assert_eq!(original, decoded);
     }
+
+    #[test]
+    pub fn test_sign_and_verify() {
+        let keypair = Keypair::random();
+        let message = [42; 32];
+
+        let signature = keypair.sk.sign(&message, 2);
+
+        assert!(signature.verify(&message, 2, &keypair.pk));
+        assert_eq!(signature, Signature::new(&message, 2, &keypair.sk));
+    }
+
+    #[test]
+    #[cfg(not(debug_assertions))]
+    pub fn test_sign_wrong_domain() {
+        let keypair = Keypair::random();
+        let message = [42; 32];
+
+        let signature = keypair.sk.sign(&message, 2);
+
+        assert!(!signature.verify(&message, 3, &keypair.pk));
+    }
+
+    #[test]
+    #[cfg(feature = "threshold-keys")]
+    pub fn test_split_and_reconstruct() {
+        let original = SecretKey::random_with_rng(&mut thread_rng());
+
+        let shares = original.split(5, 3);
+        assert_eq!(
+            shares.iter().map(|(i, _)| *i).collect::<Vec<usize>>(),
+            vec![1, 2, 3, 4, 5]
+        );
+
+        // Every subset of three shares.
+        for a in 0..5 {
+            for b in a + 1..5 {
+                for c in b + 1..5 {
+                    let subset = [shares[a].clone(), shares[b].clone(), shares[c].clone()];
+                    assert_eq!(SecretKey::reconstruct(&subset), Some(original.clone()));
+                }
+            }
+        }
+        assert_eq!(SecretKey::reconstruct(&shares), Some(original.clone()));
+
+        // Below the threshold, the key is not recovered.
+        assert_ne!(SecretKey::reconstruct(&shares[..2]), Some(original.clone()));
+
+        // A threshold of one gives every share the key itself.
+        for (_, share) in original.split(3, 1) {
+            assert_eq!(share, original);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "threshold-keys")]
+    pub fn test_reconstruct_invalid_shares() {
+        let shares = Keypair::random().sk.split(3, 2);
+
+        assert_eq!(SecretKey::reconstruct(&[]), None);
+        assert_eq!(
+            SecretKey::reconstruct(&[shares[0].clone(), shares[0].clone()]),
+            None
+        );
+        assert_eq!(
+            SecretKey::reconstruct(&[(0, shares[0].1.clone()), shares[1].clone()]),
+            None
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "secret-serialization")]
+    pub fn test_to_bytes_round_trip() {
+        use super::super::PublicKey;
+
+        let keypair = Keypair::random();
+
+        let bytes = keypair.sk.to_bytes();
+        let decoded = SecretKey::from_bytes(&bytes[..]).unwrap();
+
+        assert_eq!(PublicKey::from_secret_key(&decoded), keypair.pk);
+    }
 }
@@ -1,10 +1,16 @@
 use tiny_keccak::Keccak;
 
 pub fn hash(input: &[u8]) -> Vec<u8> {
+    hash_fixed(input).to_vec()
+}
+
+/// Identical to `hash`, however the result is returned in a fixed-length array which avoids a
+/// heap allocation.
+pub fn hash_fixed(input: &[u8]) -> [u8; 32] {
     let mut keccak = Keccak::new_keccak256();
     keccak.update(input);
-    let mut result = vec![0; 32];
-    keccak.finalize(result.as_mut_slice());
+    let mut result = [0; 32];
+    keccak.finalize(&mut result);
     result
 }
 
@@ -24,5 +30,6 @@ mod tests {
             0xa3, 0x6d, 0xea, 0xc8,
         ];
         assert_eq!(expected, output.as_slice());
+        assert_eq!(expected, &hash_fixed(input.as_ref()));
     }
 }
@@ -1,6 +1,7 @@
 use hex;
 use hex::ToHex;
-use serde::de::{self, Visitor};
+use serde::de::{self, Deserializer, Visitor};
+use serde::ser::Serializer;
 use std::fmt;
 
 pub fn encode<T: AsRef<[u8]>>(data: T) -> String {
@@ -56,9 +57,150 @@ impl<'de> Visitor<'de> for HexVisitor {
     }
 }
 
+/// Reads either a hex string (irrelevant of prefix) or a sequence of bytes (e.g., a JSON array of
+/// integers).
+///
+/// Must be used with `Deserializer::deserialize_any`, which requires a self-describing format.
+pub struct HexOrSeqVisitor;
+
+impl<'de> Visitor<'de> for HexOrSeqVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a hex string (irrelevant of prefix) or a sequence of bytes")
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        HexVisitor.visit_str(value)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::SeqAccess<'de>,
+    {
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte);
+        }
+        Ok(bytes)
+    }
+}
+
+/// Reads a raw byte buffer, for use with formats which are not human-readable (e.g., bincode).
+pub struct BytesVisitor;
+
+impl<'de> Visitor<'de> for BytesVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a byte buffer")
+    }
+
+    fn visit_bytes<E>(self, value: &[u8]) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(value.to_vec())
+    }
+
+    fn visit_byte_buf<E>(self, value: Vec<u8>) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(value)
+    }
+}
+
+/// Serializes a fixed-length byte array as a `0x`-prefixed hex string.
+///
+/// Use with `#[serde(serialize_with = "serde_hex::serialize_fixed")]`, or see `fixed`.
+pub fn serialize_fixed<S, const N: usize>(bytes: &[u8; N], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_str(&encode(&bytes[..]))
+}
+
+/// Deserializes a hex string (irrelevant of prefix) directly into a fixed-length byte array,
+/// returning an error if the string does not represent exactly `N` bytes.
+///
+/// Use with `#[serde(deserialize_with = "serde_hex::deserialize_fixed")]`, or see `fixed`.
+pub fn deserialize_fixed<'de, D, const N: usize>(deserializer: D) -> Result<[u8; N], D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_str(FixedHexVisitor::<N>)
+}
+
+/// Provides `serialize_fixed` and `deserialize_fixed` for use with
+/// `#[serde(with = "serde_hex::fixed")]`.
+pub mod fixed {
+    pub use super::deserialize_fixed as deserialize;
+    pub use super::serialize_fixed as serialize;
+}
+
+/// Decodes a hex string (irrelevant of prefix) into a `[u8; N]`.
+struct FixedHexVisitor<const N: usize>;
+
+impl<'de, const N: usize> Visitor<'de> for FixedHexVisitor<N> {
+    type Value = [u8; N];
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "a hex string of {} bytes (irrelevant of prefix)",
+            N
+        )
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        let hex = value.trim_start_matches("0x").as_bytes();
+
+        if hex.len() != N * 2 {
+            return Err(de::Error::custom(format!(
+                "invalid length (expected {} hex characters, got {})",
+                N * 2,
+                hex.len()
+            )));
+        }
+
+        let mut array = [0; N];
+        for (byte, pair) in array.iter_mut().zip(hex.chunks(2)) {
+            *byte = (hex_value(pair[0])? << 4) | hex_value(pair[1])?;
+        }
+
+        Ok(array)
+    }
+}
+
+/// Returns the value of a single hex character.
+fn hex_value<E>(c: u8) -> Result<u8, E>
+where
+    E: de::Error,
+{
+    match c {
+        b'0'..=b'9' => Ok(c - b'0'),
+        b'a'..=b'f' => Ok(c - b'a' + 10),
+        b'A'..=b'F' => Ok(c - b'A' + 10),
+        _ => Err(de::Error::custom(format!(
+            "invalid hex character ({:?})",
+            c as char
+        ))),
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
+    use serde::de::value::{Error as ValueError, StrDeserializer};
+    use serde::de::IntoDeserializer;
+    use serde_derive::{Deserialize, Serialize};
 
     #[test]
     fn encoding() {
@@ -74,4 +216,55 @@ mod test {
         let hex = encode(&bytes);
         assert_eq!(hex.as_str(), "0x010203");
     }
+
+    fn decode_fixed_32(hex: &str) -> Result<[u8; 32], ValueError> {
+        let deserializer: StrDeserializer<ValueError> = hex.into_deserializer();
+        deserialize_fixed(deserializer)
+    }
+
+    #[test]
+    fn deserialize_fixed_exact() {
+        let mut expected = [0; 32];
+        expected[0] = 0xab;
+        expected[31] = 0x01;
+
+        let hex = format!("ab{}01", "00".repeat(30));
+        assert_eq!(decode_fixed_32(&hex), Ok(expected));
+        assert_eq!(decode_fixed_32(&format!("0x{}", hex)), Ok(expected));
+        assert_eq!(decode_fixed_32(&hex.to_uppercase()), Ok(expected));
+    }
+
+    #[test]
+    fn deserialize_fixed_short() {
+        assert!(decode_fixed_32(&"00".repeat(31)).is_err());
+        assert!(decode_fixed_32("0x").is_err());
+    }
+
+    #[test]
+    fn deserialize_fixed_long() {
+        assert!(decode_fixed_32(&"00".repeat(33)).is_err());
+        assert!(decode_fixed_32(&format!("0x{}0", "00".repeat(32))).is_err());
+    }
+
+    #[test]
+    fn deserialize_fixed_invalid_character() {
+        assert!(decode_fixed_32(&format!("zz{}", "00".repeat(31))).is_err());
+    }
+
+    #[test]
+    fn fixed_round_trip() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Root {
+            #[serde(with = "fixed")]
+            root: [u8; 32],
+        }
+
+        let original = Root { root: [0xff; 32] };
+
+        let json = serde_json::to_string(&original).unwrap();
+        assert_eq!(json, format!("{{\"root\":\"0x{}\"}}", "ff".repeat(32)));
+
+        let decoded: Root = serde_json::from_str(&json).unwrap();
+        assert_eq!(original, decoded);
+    }
 }
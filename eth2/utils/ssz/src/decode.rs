@@ -5,6 +5,20 @@ pub enum DecodeError {
     TooShort,
     TooLong,
     Invalid,
+    /// The bytes do not represent a valid BLS group element (e.g., a public key).
+    BlsInvalidPoint(BlsPointError),
+    /// The bytes are not valid snappy block-compressed data (see `ssz_snappy_decode`).
+    SnappyDecompression,
+}
+
+/// The reason some bytes are not a valid BLS group element.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum BlsPointError {
+    /// The flags or coordinates are not a valid encoding of any point.
+    MalformedEncoding,
+    /// The encoding is well-formed, but the point is not on the curve (or not in the prime-order
+    /// subgroup, the two are not distinguished).
+    NotOnCurve,
 }
 
 pub trait Decodable: Sized {
@@ -46,6 +46,13 @@ impl SszStream {
         self.buffer.extend_from_slice(&vec);
     }
 
+    /// Append the bytes of a fixed-length field (e.g., a BLS public key) to the stream.
+    ///
+    /// Fixed-length fields have no length prefix, the bytes are concatenated to the stream.
+    pub fn append_fixed_bytes(&mut self, bytes: &[u8]) {
+        self.buffer.extend_from_slice(bytes);
+    }
+
     /// Append some vector (list) of encodable values to the stream.
     ///
     /// The length of the list will be concatenated to the stream, then
@@ -122,6 +129,19 @@ mod tests {
         encode_length(4294967296, LENGTH_BYTES); // 2^(3*8)
     }
 
+    #[test]
+    fn test_encode_fixed_bytes() {
+        let bytes = vec![42; 48];
+
+        let mut fixed = SszStream::new();
+        fixed.append_fixed_bytes(&bytes);
+
+        let mut raw = SszStream::new();
+        raw.append_encoded_raw(&bytes);
+
+        assert_eq!(fixed.drain(), raw.drain());
+    }
+
     #[test]
     fn test_encode_list() {
         let test_vec: Vec<u16> = vec![256; 12];
@@ -48,6 +48,10 @@ impl TreeHash for H256 {
     fn hash_tree_root(&self) -> Vec<u8> {
         ssz_encode(self)
     }
+
+    fn tree_hash_root(&self) -> H256 {
+        *self
+    }
 }
 
 impl TreeHash for [u8] {
@@ -73,6 +77,35 @@ where
     }
 }
 
+impl<A, B> TreeHash for (A, B)
+where
+    A: TreeHash,
+    B: TreeHash,
+{
+    /// Returns the merkle_hash of the hash_tree_root of each element, as if the tuple were a
+    /// container with two fields.
+    fn hash_tree_root(&self) -> Vec<u8> {
+        merkle_hash(&mut vec![self.0.hash_tree_root(), self.1.hash_tree_root()])
+    }
+}
+
+impl<A, B, C> TreeHash for (A, B, C)
+where
+    A: TreeHash,
+    B: TreeHash,
+    C: TreeHash,
+{
+    /// Returns the merkle_hash of the hash_tree_root of each element, as if the tuple were a
+    /// container with three fields.
+    fn hash_tree_root(&self) -> Vec<u8> {
+        merkle_hash(&mut vec![
+            self.0.hash_tree_root(),
+            self.1.hash_tree_root(),
+            self.2.hash_tree_root(),
+        ])
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -82,4 +115,39 @@ mod tests {
         let result = vec![1u32, 2, 3, 4, 5, 6, 7].hash_tree_root();
         assert_eq!(result.len(), 32);
     }
+
+    #[test]
+    fn test_impl_tree_hash_tuple() {
+        let a = 42_u64;
+        let b = H256::from_low_u64_le(7);
+        let c = true;
+
+        let expected = merkle_hash(&mut vec![a.hash_tree_root(), b.hash_tree_root()]);
+        assert_eq!((a, b).hash_tree_root(), expected);
+
+        let expected = merkle_hash(&mut vec![
+            a.hash_tree_root(),
+            b.hash_tree_root(),
+            c.hash_tree_root(),
+        ]);
+        assert_eq!((a, b, c).hash_tree_root(), expected);
+
+        assert_ne!((a, b).hash_tree_root(), (b, a).hash_tree_root());
+    }
+
+    #[test]
+    fn test_tree_hash_root() {
+        let list = vec![1u32, 2, 3, 4, 5, 6, 7];
+        assert_eq!(
+            list.tree_hash_root(),
+            H256::from_slice(&list.hash_tree_root())
+        );
+
+        let h = H256::from_low_u64_le(7);
+        assert_eq!(h.tree_hash_root(), h);
+
+        let mut padded = ssz_encode(&42_u64);
+        padded.resize(32, 0);
+        assert_eq!(42_u64.tree_hash_root(), H256::from_slice(&padded));
+    }
 }
@@ -13,18 +13,22 @@ extern crate ethereum_types;
 pub mod decode;
 pub mod encode;
 mod signed_root;
+#[cfg(feature = "snappy")]
+mod snappy;
 pub mod tree_hash;
 
 mod impl_decode;
 mod impl_encode;
 mod impl_tree_hash;
 
-pub use crate::decode::{decode, decode_ssz_list, Decodable, DecodeError};
+pub use crate::decode::{decode, decode_ssz_list, BlsPointError, Decodable, DecodeError};
 pub use crate::encode::{Encodable, SszStream};
 pub use crate::signed_root::SignedRoot;
+#[cfg(feature = "snappy")]
+pub use crate::snappy::{ssz_snappy_decode, ssz_snappy_encode};
 pub use crate::tree_hash::{merkle_hash, TreeHash};
 
-pub use hashing::hash;
+pub use hashing::{hash, hash_fixed};
 
 pub const LENGTH_BYTES: usize = 4;
 pub const MAX_LIST_SIZE: usize = 1 << (4 * 8);
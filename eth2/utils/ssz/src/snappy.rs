@@ -0,0 +1,48 @@
+//! SSZ bytes wrapped in snappy block compression, as used by the Eth2 req/resp protocol.
+
+use crate::{decode, ssz_encode, Decodable, DecodeError, Encodable};
+
+/// Returns the SSZ encoding of `value`, compressed in the snappy block format.
+pub fn ssz_snappy_encode<T: Encodable>(value: &T) -> Vec<u8> {
+    snap::Encoder::new()
+        .compress_vec(&ssz_encode(value))
+        .expect("snappy only fails to compress inputs larger than 4 GiB")
+}
+
+/// Decompresses `bytes` from the snappy block format and decodes the SSZ result as a `T`.
+///
+/// Returns `DecodeError::SnappyDecompression` if `bytes` are not valid snappy data.
+pub fn ssz_snappy_decode<T: Decodable>(bytes: &[u8]) -> Result<T, DecodeError> {
+    let ssz_bytes = snap::Decoder::new()
+        .decompress_vec(bytes)
+        .map_err(|_| DecodeError::SnappyDecompression)?;
+
+    decode(&ssz_bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn test_round_trip() {
+        let value: Vec<u64> = (0..64).collect();
+
+        let bytes = ssz_snappy_encode(&value);
+        assert_ne!(bytes, ssz_encode(&value));
+        assert_eq!(ssz_snappy_decode::<Vec<u64>>(&bytes), Ok(value));
+    }
+
+    #[test]
+    pub fn test_decode_errors() {
+        // A length prefix longer than the (empty) data.
+        assert_eq!(
+            ssz_snappy_decode::<u64>(&[0x05]),
+            Err(DecodeError::SnappyDecompression)
+        );
+
+        // Valid snappy data, which is not a valid SSZ `u64`.
+        let bytes = ssz_snappy_encode(&42_u32);
+        assert_eq!(ssz_snappy_decode::<u64>(&bytes), Err(DecodeError::TooShort));
+    }
+}
@@ -1,3 +1,4 @@
+use ethereum_types::H256;
 use hashing::hash;
 
 const BYTES_PER_CHUNK: usize = 32;
@@ -5,6 +6,21 @@ const HASHSIZE: usize = 32;
 
 pub trait TreeHash {
     fn hash_tree_root(&self) -> Vec<u8>;
+
+    /// Returns the `hash_tree_root` as a `H256`.
+    ///
+    /// Roots shorter than 32 bytes (e.g., for integers) are right-padded with zeros.
+    ///
+    /// The default implementation converts the result of `hash_tree_root`, implementations in
+    /// hot paths should override it to avoid the intermediate `Vec`.
+    fn tree_hash_root(&self) -> H256 {
+        let root = self.hash_tree_root();
+        let len = std::cmp::min(root.len(), HASHSIZE);
+
+        let mut bytes = [0; HASHSIZE];
+        bytes[..len].copy_from_slice(&root[..len]);
+        H256::from(bytes)
+    }
 }
 
 /// Returns a 32 byte hash of 'list' - a vector of byte vectors.